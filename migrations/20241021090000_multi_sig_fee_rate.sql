@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE multi_sig_info ADD COLUMN IF NOT EXISTS fee_rate BIGINT NOT NULL DEFAULT 1000;
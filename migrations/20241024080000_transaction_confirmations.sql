@@ -0,0 +1,7 @@
+-- Add migration script here
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS block_number BIGINT;
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS block_hash VARCHAR(100);
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS confirmations BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS broadcast_at TIMESTAMP;
+
+CREATE INDEX IF NOT EXISTS transactions_status_index ON transactions (status);
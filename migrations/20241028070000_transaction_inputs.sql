@@ -0,0 +1,20 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS transaction_inputs (
+  transaction_id VARCHAR(100) NOT NULL,
+  tx_hash VARCHAR(100) NOT NULL,
+  output_index INTEGER NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (transaction_id, tx_hash, output_index)
+);
+
+CREATE INDEX transaction_inputs_outpoint_index ON transaction_inputs (tx_hash, output_index);
+
+-- Backfill inputs of existing proposals from their payload
+INSERT INTO transaction_inputs (transaction_id, tx_hash, output_index)
+SELECT
+  tx.transaction_id,
+  substring(input->'previous_output'->>'tx_hash' from 3),
+  ('x' || lpad(substring(input->'previous_output'->>'index' from 3), 8, '0'))::bit(32)::int
+FROM transactions tx, jsonb_array_elements(tx.payload::jsonb->'inputs') input
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,12 @@
+-- Add migration script here
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS proposer_address VARCHAR(200);
+
+-- The proposer is the first signer of a proposal
+UPDATE transactions tx SET proposer_address = (
+  SELECT sig.signer_address
+  FROM signatures sig
+  WHERE sig.transaction_id = tx.transaction_id
+  ORDER BY sig.created_at ASC
+  LIMIT 1
+)
+WHERE proposer_address IS NULL;
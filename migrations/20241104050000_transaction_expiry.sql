@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS expires_at TIMESTAMP;
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS status_reason TEXT;
+ALTER TABLE multi_sig_info ADD COLUMN IF NOT EXISTS proposal_validity_hours INTEGER;
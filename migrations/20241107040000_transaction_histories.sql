@@ -0,0 +1,11 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS transaction_histories (
+  id SERIAL PRIMARY KEY,
+  transaction_id VARCHAR(100) NOT NULL,
+  actor_address VARCHAR(200),
+  action VARCHAR(50) NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX transaction_histories_txid_index ON transaction_histories (transaction_id);
@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE multi_sig_info ADD COLUMN IF NOT EXISTS auto_broadcast BOOLEAN NOT NULL DEFAULT TRUE;
@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE transaction_errors ADD COLUMN IF NOT EXISTS category VARCHAR(50);
@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS preflight_status VARCHAR(20);
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS preflight_message TEXT;
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS preflight_at TIMESTAMP;
@@ -0,0 +1,12 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS transaction_comments (
+  id SERIAL PRIMARY KEY,
+  transaction_id VARCHAR(100) NOT NULL,
+  author_address VARCHAR(200) NOT NULL,
+  body TEXT NOT NULL,
+  edited_at TIMESTAMP,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX transaction_comments_txid_index ON transaction_comments (transaction_id);
@@ -0,0 +1,15 @@
+-- Add migration script here
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS memo TEXT;
+
+-- Keyed by tx hash so incoming transfers, which have no proposal, can be labelled as well
+CREATE TABLE IF NOT EXISTS transaction_labels (
+  multi_sig_address VARCHAR(200) NOT NULL,
+  tx_hash VARCHAR(100) NOT NULL,
+  category VARCHAR(50),
+  labels TEXT[] NOT NULL DEFAULT '{}',
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (multi_sig_address, tx_hash)
+);
+
+CREATE INDEX transaction_labels_labels_index ON transaction_labels USING GIN (labels);
@@ -0,0 +1,23 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS multi_sig_messages (
+  id SERIAL PRIMARY KEY,
+  multi_sig_address VARCHAR(200) NOT NULL,
+  proposer_address VARCHAR(200) NOT NULL,
+  message TEXT NOT NULL,
+  digest VARCHAR(100) NOT NULL,
+  status SMALLINT NOT NULL DEFAULT 0,
+  proof TEXT,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX multi_sig_messages_address_index ON multi_sig_messages (multi_sig_address);
+
+CREATE TABLE IF NOT EXISTS multi_sig_message_signatures (
+  message_id INTEGER NOT NULL REFERENCES multi_sig_messages (id),
+  signer_address VARCHAR(200) NOT NULL,
+  signature VARCHAR(200) NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (message_id, signer_address)
+);
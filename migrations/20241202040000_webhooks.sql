@@ -0,0 +1,34 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS webhooks (
+  id SERIAL PRIMARY KEY,
+  multi_sig_address VARCHAR(200) NOT NULL,
+  url TEXT NOT NULL,
+  secret VARCHAR(100) NOT NULL,
+  events TEXT[] NOT NULL DEFAULT '{}',
+  active BOOLEAN NOT NULL DEFAULT TRUE,
+  created_by VARCHAR(200) NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX webhooks_multi_sig_address_index ON webhooks (multi_sig_address);
+
+-- The delivery queue, rows stay as the delivery log once they are delivered or gave up
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+  id SERIAL PRIMARY KEY,
+  webhook_id INTEGER NOT NULL REFERENCES webhooks (id) ON DELETE CASCADE,
+  event VARCHAR(50) NOT NULL,
+  payload TEXT NOT NULL,
+  status SMALLINT NOT NULL DEFAULT 0,
+  attempts INTEGER NOT NULL DEFAULT 0,
+  next_attempt_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  response_status INTEGER,
+  response_body TEXT,
+  last_error TEXT,
+  delivered_at TIMESTAMP,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX webhook_deliveries_webhook_id_index ON webhook_deliveries (webhook_id, created_at);
+CREATE INDEX webhook_deliveries_queue_index ON webhook_deliveries (next_attempt_at) WHERE status = 0;
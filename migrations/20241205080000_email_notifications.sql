@@ -0,0 +1,37 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS user_emails (
+  user_address VARCHAR(200) PRIMARY KEY,
+  email VARCHAR(320) NOT NULL,
+  verified_at TIMESTAMP,
+  verification_token VARCHAR(100),
+  verification_expires_at TIMESTAMP,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- Events without a row fall back to the defaults of the application
+CREATE TABLE IF NOT EXISTS notification_preferences (
+  user_address VARCHAR(200) NOT NULL,
+  event VARCHAR(50) NOT NULL,
+  email BOOLEAN NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (user_address, event)
+);
+
+CREATE TABLE IF NOT EXISTS email_outbox (
+  id SERIAL PRIMARY KEY,
+  recipient VARCHAR(320) NOT NULL,
+  subject TEXT NOT NULL,
+  html_body TEXT NOT NULL,
+  text_body TEXT NOT NULL,
+  status SMALLINT NOT NULL DEFAULT 0,
+  attempts INTEGER NOT NULL DEFAULT 0,
+  next_attempt_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  last_error TEXT,
+  sent_at TIMESTAMP,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX email_outbox_queue_index ON email_outbox (next_attempt_at) WHERE status = 0;
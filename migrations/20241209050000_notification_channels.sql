@@ -0,0 +1,16 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS notification_channels (
+  id SERIAL PRIMARY KEY,
+  multi_sig_address VARCHAR(200) NOT NULL,
+  kind VARCHAR(20) NOT NULL,
+  -- Telegram chat id, or the incoming webhook url of Discord and Slack
+  target TEXT NOT NULL,
+  events TEXT[] NOT NULL DEFAULT '{}',
+  active BOOLEAN NOT NULL DEFAULT TRUE,
+  created_by VARCHAR(200) NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX notification_channels_multi_sig_address_index
+  ON notification_channels (multi_sig_address);
@@ -0,0 +1,21 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS notifications (
+  id SERIAL PRIMARY KEY,
+  user_address VARCHAR(200) NOT NULL,
+  event VARCHAR(50) NOT NULL,
+  multi_sig_address VARCHAR(200) NOT NULL,
+  transaction_id VARCHAR(200),
+  actor_address VARCHAR(200),
+  -- rendered when the event happens, so the inbox reads the same later on
+  title TEXT NOT NULL,
+  body TEXT NOT NULL,
+  link TEXT NOT NULL,
+  read_at TIMESTAMP,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX notifications_user_address_index
+  ON notifications (user_address, created_at DESC);
+
+CREATE INDEX notifications_unread_index
+  ON notifications (user_address) WHERE read_at IS NULL;
@@ -0,0 +1,6 @@
+-- Add migration script here
+-- Set on proposals which cancel a fully signed proposal on-chain
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS cancels_transaction_id VARCHAR(200);
+
+CREATE INDEX IF NOT EXISTS transactions_cancels_transaction_id_index
+  ON transactions (cancels_transaction_id);
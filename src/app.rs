@@ -1,6 +1,9 @@
 use crate::{
     config,
-    handlers::{address_book, ckb_explorer, multi_sig_account},
+    handlers::{
+        address_book, ckb_explorer, email, events, multi_sig_account, multi_sig_message,
+        notification, notification_channel, transaction_comment, webhook,
+    },
     repositories::{self, db::DB_POOL},
     services,
 };
@@ -13,6 +16,13 @@ pub fn init_routes(cfg: &mut web::ServiceConfig) {
     user::route(cfg);
     multi_sig_account::route(cfg);
     address_book::route(cfg);
+    transaction_comment::route(cfg);
+    multi_sig_message::route(cfg);
+    webhook::route(cfg);
+    events::route(cfg);
+    email::route(cfg);
+    notification_channel::route(cfg);
+    notification::route(cfg);
     ckb_explorer::route(cfg);
 }
 
@@ -22,14 +32,76 @@ pub async fn create_app() -> std::io::Result<()> {
     let user_dao = repositories::user::UserDao::new(db.clone());
     let multi_sig_dao = repositories::multi_sig_account::MultiSigDao::new(db.clone());
     let address_book_dao = repositories::address_book::AddressBookDao::new(db.clone());
+    let transaction_comment_dao =
+        repositories::transaction_comment::TransactionCommentDao::new(db.clone());
+    let multi_sig_message_dao =
+        repositories::multi_sig_message::MultiSigMessageDao::new(db.clone());
+    let webhook_dao = repositories::webhook::WebhookDao::new(db.clone());
+    let event_dao = repositories::event::EventDao::new(db.clone());
+    let email_dao = repositories::email::EmailDao::new(db.clone());
+    let notification_channel_dao =
+        repositories::notification_channel::NotificationChannelDao::new(db.clone());
+    let notification_dao = repositories::notification::NotificationDao::new(db.clone());
+    let event_dispatcher = services::events::EventDispatcher::new(
+        webhook_dao.clone(),
+        event_dao.clone(),
+        email_dao.clone(),
+        notification_channel_dao.clone(),
+        notification_dao.clone(),
+    );
+    let event_hub = services::events::EventHub::new(services::events::EVENT_HUB_CAPACITY);
     let user_service = web::Data::new(services::user::UserSrv::new(user_dao));
     let multi_sig_service = web::Data::new(services::multi_sig_account::MultiSigSrv::new(
-        multi_sig_dao,
+        multi_sig_dao.clone(),
         address_book_dao.clone(),
+        transaction_comment_dao.clone(),
+        event_dispatcher.clone(),
     ));
     let address_book_service = web::Data::new(services::address_book::AddressBookSrv::new(
         address_book_dao.clone(),
     ));
+    let transaction_comment_service =
+        web::Data::new(services::transaction_comment::TransactionCommentSrv::new(
+            transaction_comment_dao.clone(),
+            multi_sig_dao.clone(),
+        ));
+    let multi_sig_message_service =
+        web::Data::new(services::multi_sig_message::MultiSigMessageSrv::new(
+            multi_sig_message_dao.clone(),
+            multi_sig_dao.clone(),
+        ));
+    let webhook_service = web::Data::new(services::webhook::WebhookSrv::new(
+        webhook_dao.clone(),
+        multi_sig_dao.clone(),
+    ));
+    let event_stream_service = web::Data::new(services::events::EventStreamSrv::new(
+        event_hub.clone(),
+        multi_sig_dao.clone(),
+    ));
+    let email_service = web::Data::new(services::email::EmailSrv::new(email_dao.clone()));
+    let notification_channel_service =
+        web::Data::new(services::notification_channel::NotificationChannelSrv::new(
+            notification_channel_dao.clone(),
+            multi_sig_dao.clone(),
+        ));
+    let notification_service = web::Data::new(services::notification::NotificationSrv::new(
+        notification_dao.clone(),
+    ));
+
+    // Background workers
+    tokio::spawn(services::worker::run_confirmation_tracker(
+        multi_sig_service.get_ref().clone(),
+    ));
+    tokio::spawn(services::worker::run_expiry_sweep(
+        multi_sig_service.get_ref().clone(),
+    ));
+    tokio::spawn(services::worker::run_webhook_delivery(
+        webhook_service.get_ref().clone(),
+    ));
+    tokio::spawn(services::worker::run_event_listener(event_hub.clone()));
+    tokio::spawn(services::worker::run_email_outbox(
+        email_service.get_ref().clone(),
+    ));
 
     let listen_address: String = config::get("listen_address");
 
@@ -46,6 +118,13 @@ pub async fn create_app() -> std::io::Result<()> {
             .app_data(user_service.clone())
             .app_data(multi_sig_service.clone())
             .app_data(address_book_service.clone())
+            .app_data(transaction_comment_service.clone())
+            .app_data(multi_sig_message_service.clone())
+            .app_data(webhook_service.clone())
+            .app_data(event_stream_service.clone())
+            .app_data(email_service.clone())
+            .app_data(notification_channel_service.clone())
+            .app_data(notification_service.clone())
             .wrap(cors)
             .wrap(middleware::Logger::default())
             .configure(init_routes)
@@ -0,0 +1,124 @@
+use crate::{
+    serialize::{
+        email::{EmailPreferencesReq, RegisterEmailReq, VerifyEmailReq},
+        error::AppError,
+    },
+    services::email::EmailSrv,
+};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+
+use super::jwt::JwtMiddleware;
+
+async fn request_get_email(
+    email_srv: web::Data<EmailSrv>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match email_srv.get_email(&user_address).await {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_register_email(
+    email_srv: web::Data<EmailSrv>,
+    req: web::Json<RegisterEmailReq>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match email_srv
+        .register_email(&user_address, req.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_delete_email(
+    email_srv: web::Data<EmailSrv>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match email_srv.delete_email(&user_address).await {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_verify_email(
+    email_srv: web::Data<EmailSrv>,
+    req: web::Json<VerifyEmailReq>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match email_srv
+        .verify_email(&user_address, req.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_get_preferences(
+    email_srv: web::Data<EmailSrv>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match email_srv.get_preferences(&user_address).await {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_update_preferences(
+    email_srv: web::Data<EmailSrv>,
+    req: web::Json<EmailPreferencesReq>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match email_srv
+        .update_preferences(&user_address, req.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+pub fn route(conf: &mut web::ServiceConfig) {
+    conf.service(
+        web::scope("/email")
+            .route("", web::get().to(request_get_email))
+            .route("", web::put().to(request_register_email))
+            .route("", web::delete().to(request_delete_email))
+            .route("/verify", web::post().to(request_verify_email))
+            .route("/preferences", web::get().to(request_get_preferences))
+            .route("/preferences", web::put().to(request_update_preferences)),
+    );
+}
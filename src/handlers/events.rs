@@ -0,0 +1,33 @@
+use crate::{
+    serialize::{error::AppError, event::EventStreamFilters},
+    services::events::EventStreamSrv,
+};
+use actix_web::{http::header, web, HttpMessage, HttpRequest, HttpResponse};
+
+use super::jwt::JwtMiddleware;
+
+async fn request_event_stream(
+    event_stream_srv: web::Data<EventStreamSrv>,
+    filters: web::Query<EventStreamFilters>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    let events = event_stream_srv
+        .stream_events(&user_address, filters.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        // let nginx pass events through as they come
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(events))
+}
+
+pub fn route(conf: &mut web::ServiceConfig) {
+    conf.service(web::scope("/events").route("/stream", web::get().to(request_event_stream)));
+}
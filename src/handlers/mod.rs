@@ -1,5 +1,12 @@
 pub mod address_book;
 pub mod ckb_explorer;
+pub mod email;
+pub mod events;
 pub mod jwt;
 pub mod multi_sig_account;
+pub mod multi_sig_message;
+pub mod notification;
+pub mod notification_channel;
+pub mod transaction_comment;
 pub mod user;
+pub mod webhook;
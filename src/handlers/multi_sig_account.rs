@@ -1,15 +1,24 @@
 use crate::{
     models::multi_sig_invite::MultiSigInviteStatus,
     serialize::{
+        ckb_cli::CkbCliTx,
+        cobuild::BuildingPacketReq,
         error::AppError,
+        ledger::LedgerContextFilters,
         multi_sig_account::{
             InviteStatusReq, MultiSigAccountUpdateReq, NewMultiSigAccountReq, NewTransferReq,
-            SubmitSignatureReq, TransactionFilters, UpdateTransactionStatusReq,
+            OnChainCancelReq, SubmitSignatureReq, TransactionExportFilters, TransactionFilters,
+            TransactionLabelReq, UpdateTransactionStatusReq,
         },
+        neuron::NeuronOfflineTx,
+        ur::{UrFragmentFilters, UrSignatureReq},
+    },
+    services::{
+        multi_sig_account::MultiSigSrv,
+        ur::{DEFAULT_FRAGMENT_LEN, MAX_FRAGMENT_LEN},
     },
-    services::multi_sig_account::MultiSigSrv,
 };
-use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+use actix_web::{http::header, web, HttpMessage, HttpRequest, HttpResponse};
 use serde_json::json;
 
 use super::jwt::JwtMiddleware;
@@ -144,6 +153,34 @@ async fn request_list_transactions(
     }
 }
 
+async fn request_export_transactions(
+    filters: web::Query<TransactionExportFilters>,
+    multisig_address: web::Path<String>,
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    let (format, rows) = multi_sig_srv
+        .export_transactions(&user_address, &multisig_address, filters.into_inner())
+        .await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"transactions-{}.{}\"",
+                multisig_address,
+                format.extension()
+            ),
+        ))
+        .streaming(rows))
+}
+
 async fn create_new_account(
     multi_sig_srv: web::Data<MultiSigSrv>,
     req: web::Json<NewMultiSigAccountReq>,
@@ -199,7 +236,7 @@ async fn create_new_transfer(
     }
 
     match multi_sig_srv
-        .create_new_transfer(&user_address, &req.signature, &req.payload)
+        .create_new_transfer(&user_address, req.clone())
         .await
     {
         Ok(res) => Ok(HttpResponse::Ok().json(res)),
@@ -235,6 +272,86 @@ async fn submit_signature(
     }
 }
 
+async fn revoke_signature(
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    transaction_id: web::Path<String>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+
+    match multi_sig_srv
+        .revoke_signature(&user_address, &transaction_id)
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(json!({ "result": res }))),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_transaction_history(
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    transaction_id: web::Path<String>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+
+    match multi_sig_srv
+        .request_transaction_history(&user_address, &transaction_id)
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn execute_transaction(
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    transaction_id: web::Path<String>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+
+    match multi_sig_srv
+        .execute_transaction(&user_address, &transaction_id)
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn retry_transaction(
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    transaction_id: web::Path<String>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+
+    match multi_sig_srv
+        .retry_transaction(&user_address, &transaction_id)
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
 async fn reject_transaction(
     multi_sig_srv: web::Data<MultiSigSrv>,
     transaction_id: web::Path<String>,
@@ -255,6 +372,67 @@ async fn reject_transaction(
     }
 }
 
+async fn cancel_transaction(
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    transaction_id: web::Path<String>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+
+    match multi_sig_srv
+        .cancel_transaction(&user_address, &transaction_id)
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(json!({ "result": res }))),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_onchain_cancellation(
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    transaction_id: web::Path<String>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+
+    match multi_sig_srv
+        .build_onchain_cancellation(&user_address, &transaction_id)
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn propose_onchain_cancellation(
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    transaction_id: web::Path<String>,
+    req: web::Json<OnChainCancelReq>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+
+    match multi_sig_srv
+        .propose_onchain_cancellation(&user_address, &transaction_id, req.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
 async fn request_transaction_summary(
     multisig_address: web::Path<String>,
     multi_sig_srv: web::Data<MultiSigSrv>,
@@ -274,12 +452,281 @@ async fn request_transaction_summary(
     }
 }
 
+async fn request_fee_rate_estimate(
+    multisig_address: web::Path<String>,
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match multi_sig_srv
+        .estimate_fee_rate(&user_address, &multisig_address)
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_list_labels(
+    multisig_address: web::Path<String>,
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match multi_sig_srv
+        .request_list_labels(&user_address, &multisig_address)
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_update_labels(
+    path: web::Path<(String, String)>,
+    req: web::Json<TransactionLabelReq>,
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    let (multisig_address, tx_hash) = path.into_inner();
+    match multi_sig_srv
+        .update_labels(&user_address, &multisig_address, &tx_hash, req.clone())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_export_ckb_cli_tx(
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    transaction_id: web::Path<String>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match multi_sig_srv
+        .export_ckb_cli_tx(&user_address, &transaction_id)
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_import_ckb_cli_tx(
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    req: web::Json<CkbCliTx>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match multi_sig_srv
+        .import_ckb_cli_tx(&user_address, req.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_export_building_packet(
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    transaction_id: web::Path<String>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match multi_sig_srv
+        .export_building_packet(&user_address, &transaction_id)
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_import_building_packet(
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    req: web::Json<BuildingPacketReq>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match multi_sig_srv
+        .import_building_packet(&user_address, req.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_transaction_bundle(
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    transaction_id: web::Path<String>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match multi_sig_srv
+        .request_transaction_bundle(&user_address, &transaction_id)
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_ledger_signing_context(
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    transaction_id: web::Path<String>,
+    filters: web::Query<LedgerContextFilters>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match multi_sig_srv
+        .request_ledger_signing_context(&user_address, &transaction_id, filters.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_sign_request_ur(
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    transaction_id: web::Path<String>,
+    filters: web::Query<UrFragmentFilters>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    let fragment_size = filters
+        .fragment_size
+        .unwrap_or(DEFAULT_FRAGMENT_LEN)
+        .min(MAX_FRAGMENT_LEN);
+    match multi_sig_srv
+        .request_sign_request_ur(&user_address, &transaction_id, fragment_size)
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn submit_signature_ur(
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    req: web::Json<UrSignatureReq>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+
+    if req.parts.is_empty() {
+        return Err(AppError::new(400).message("No UR part"));
+    }
+
+    match multi_sig_srv
+        .submit_signature_ur(&user_address, &req.parts)
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_export_neuron_tx(
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    transaction_id: web::Path<String>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match multi_sig_srv
+        .export_neuron_tx(&user_address, &transaction_id)
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_import_neuron_tx(
+    multi_sig_srv: web::Data<MultiSigSrv>,
+    req: web::Json<NeuronOfflineTx>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match multi_sig_srv
+        .import_neuron_tx(&user_address, req.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
 async fn update_transaction_commited(
+    multisig_address: web::Path<String>,
     req: web::Json<UpdateTransactionStatusReq>,
     multi_sig_srv: web::Data<MultiSigSrv>,
+    http_req: HttpRequest,
     _: JwtMiddleware,
 ) -> Result<HttpResponse, AppError> {
-    match multi_sig_srv.update_transaction_commited(&req).await {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match multi_sig_srv
+        .recheck_transactions(&user_address, &multisig_address, &req)
+        .await
+    {
         Ok(res) => Ok(HttpResponse::Ok().json(res)),
         Err(err) => Err(err),
     }
@@ -301,6 +748,10 @@ pub fn route(conf: &mut web::ServiceConfig) {
             )
             .route("/accounts", web::get().to(request_list_accounts))
             .route("/accounts", web::put().to(request_update_account))
+            .route(
+                "/fee-rate/{address}",
+                web::get().to(request_fee_rate_estimate),
+            )
             .route(
                 "/transactions/{address}",
                 web::get().to(request_list_transactions),
@@ -309,6 +760,10 @@ pub fn route(conf: &mut web::ServiceConfig) {
                 "/transactions/{address}/commited",
                 web::put().to(update_transaction_commited),
             )
+            .route(
+                "/transactions/{address}/export",
+                web::get().to(request_export_transactions),
+            )
             .route(
                 "/transactions/{address}/summary",
                 web::get().to(request_transaction_summary),
@@ -317,8 +772,70 @@ pub fn route(conf: &mut web::ServiceConfig) {
                 "/transactions/{txId}/reject",
                 web::put().to(reject_transaction),
             )
+            .route(
+                "/transactions/{txId}/cancel",
+                web::put().to(cancel_transaction),
+            )
+            .route(
+                "/transactions/{txId}/cancel-onchain",
+                web::get().to(request_onchain_cancellation),
+            )
+            .route(
+                "/transactions/{txId}/cancel-onchain",
+                web::post().to(propose_onchain_cancellation),
+            )
+            .route(
+                "/transactions/{txId}/execute",
+                web::post().to(execute_transaction),
+            )
+            .route(
+                "/transactions/{txId}/retry",
+                web::post().to(retry_transaction),
+            )
+            .route(
+                "/transactions/{txId}/history",
+                web::get().to(request_transaction_history),
+            )
+            .route("/labels/{address}", web::get().to(request_list_labels))
+            .route(
+                "/labels/{address}/{txHash}",
+                web::put().to(request_update_labels),
+            )
+            .route(
+                "/transactions/{txId}/export/ckb-cli",
+                web::get().to(request_export_ckb_cli_tx),
+            )
+            .route("/import/ckb-cli", web::post().to(request_import_ckb_cli_tx))
+            .route(
+                "/transactions/{txId}/export/building-packet",
+                web::get().to(request_export_building_packet),
+            )
+            .route(
+                "/import/building-packet",
+                web::post().to(request_import_building_packet),
+            )
+            .route(
+                "/transactions/{txId}/export/neuron",
+                web::get().to(request_export_neuron_tx),
+            )
+            .route("/import/neuron", web::post().to(request_import_neuron_tx))
+            .route(
+                "/transactions/{txId}/bundle",
+                web::get().to(request_transaction_bundle),
+            )
+            .route(
+                "/transactions/{txId}/export/ur",
+                web::get().to(request_sign_request_ur),
+            )
+            .route(
+                "/transactions/{txId}/export/ledger",
+                web::get().to(request_ledger_signing_context),
+            )
             .route("/new-transfer", web::post().to(create_new_transfer))
             .route("/signature", web::post().to(submit_signature))
+            // before /signature/{txId} so the UR route is not shadowed
+            .route("/signature/ur", web::post().to(submit_signature_ur))
+            .route("/signature/{txId}", web::delete().to(revoke_signature))
             .route("/new-account", web::post().to(create_new_account)),
     );
 }
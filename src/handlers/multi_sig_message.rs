@@ -0,0 +1,114 @@
+use crate::{
+    serialize::{
+        error::AppError,
+        multi_sig_message::{MessageFilters, MessageSignatureReq, NewMessageReq, VerifyMessageReq},
+    },
+    services::multi_sig_message::MultiSigMessageSrv,
+};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+
+use super::jwt::JwtMiddleware;
+
+async fn request_list_messages(
+    multi_sig_message_srv: web::Data<MultiSigMessageSrv>,
+    multi_sig_address: web::Path<String>,
+    filters: web::Query<MessageFilters>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match multi_sig_message_srv
+        .list_messages(&user_address, &multi_sig_address, filters.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_create_message(
+    multi_sig_message_srv: web::Data<MultiSigMessageSrv>,
+    multi_sig_address: web::Path<String>,
+    req: web::Json<NewMessageReq>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match multi_sig_message_srv
+        .create_message(&user_address, &multi_sig_address, req.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_get_message(
+    multi_sig_message_srv: web::Data<MultiSigMessageSrv>,
+    id: web::Path<i32>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match multi_sig_message_srv
+        .get_message(&user_address, id.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_sign_message(
+    multi_sig_message_srv: web::Data<MultiSigMessageSrv>,
+    id: web::Path<i32>,
+    req: web::Json<MessageSignatureReq>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match multi_sig_message_srv
+        .sign_message(&user_address, id.into_inner(), req.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+// Public, so third parties can check a proof they were given
+async fn request_verify_message(
+    multi_sig_message_srv: web::Data<MultiSigMessageSrv>,
+    req: web::Json<VerifyMessageReq>,
+) -> Result<HttpResponse, AppError> {
+    match multi_sig_message_srv.verify_message(req.into_inner()) {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+pub fn route(conf: &mut web::ServiceConfig) {
+    conf.service(
+        web::scope("/messages")
+            .route("/verify", web::post().to(request_verify_message))
+            .route("/accounts/{address}", web::get().to(request_list_messages))
+            .route(
+                "/accounts/{address}",
+                web::post().to(request_create_message),
+            )
+            .route("/{id}", web::get().to(request_get_message))
+            .route("/{id}/signatures", web::post().to(request_sign_message)),
+    );
+}
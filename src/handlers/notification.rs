@@ -0,0 +1,88 @@
+use crate::{
+    serialize::{
+        error::AppError,
+        notification::{MarkReadReq, NotificationFilters},
+    },
+    services::notification::NotificationSrv,
+};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+
+use super::jwt::JwtMiddleware;
+
+async fn request_list_notifications(
+    notification_srv: web::Data<NotificationSrv>,
+    filters: web::Query<NotificationFilters>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match notification_srv
+        .list_notifications(&user_address, filters.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_unread_count(
+    notification_srv: web::Data<NotificationSrv>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match notification_srv.get_unread_count(&user_address).await {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_mark_read(
+    notification_srv: web::Data<NotificationSrv>,
+    req: web::Json<MarkReadReq>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match notification_srv
+        .mark_read(&user_address, req.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_mark_all_read(
+    notification_srv: web::Data<NotificationSrv>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match notification_srv.mark_all_read(&user_address).await {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+pub fn route(conf: &mut web::ServiceConfig) {
+    conf.service(
+        web::scope("/notifications")
+            .route("", web::get().to(request_list_notifications))
+            .route("/unread-count", web::get().to(request_unread_count))
+            .route("/read", web::post().to(request_mark_read))
+            .route("/read-all", web::post().to(request_mark_all_read)),
+    );
+}
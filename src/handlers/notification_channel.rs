@@ -0,0 +1,121 @@
+use crate::{
+    serialize::{
+        error::AppError,
+        notification_channel::{NewChannelReq, UpdateChannelReq},
+    },
+    services::notification_channel::NotificationChannelSrv,
+};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+
+use super::jwt::JwtMiddleware;
+
+async fn request_list_channels(
+    notification_channel_srv: web::Data<NotificationChannelSrv>,
+    multi_sig_address: web::Path<String>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match notification_channel_srv
+        .list_channels(&user_address, &multi_sig_address)
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_create_channel(
+    notification_channel_srv: web::Data<NotificationChannelSrv>,
+    multi_sig_address: web::Path<String>,
+    req: web::Json<NewChannelReq>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match notification_channel_srv
+        .create_channel(&user_address, &multi_sig_address, req.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_update_channel(
+    notification_channel_srv: web::Data<NotificationChannelSrv>,
+    id: web::Path<i32>,
+    req: web::Json<UpdateChannelReq>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match notification_channel_srv
+        .update_channel(&user_address, id.into_inner(), req.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_delete_channel(
+    notification_channel_srv: web::Data<NotificationChannelSrv>,
+    id: web::Path<i32>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match notification_channel_srv
+        .delete_channel(&user_address, id.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_test_channel(
+    notification_channel_srv: web::Data<NotificationChannelSrv>,
+    id: web::Path<i32>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match notification_channel_srv
+        .test_channel(&user_address, id.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+pub fn route(conf: &mut web::ServiceConfig) {
+    conf.service(
+        web::scope("/channels")
+            .route("/accounts/{address}", web::get().to(request_list_channels))
+            .route(
+                "/accounts/{address}",
+                web::post().to(request_create_channel),
+            )
+            .route("/{id}", web::put().to(request_update_channel))
+            .route("/{id}", web::delete().to(request_delete_channel))
+            .route("/{id}/test", web::post().to(request_test_channel)),
+    );
+}
@@ -0,0 +1,76 @@
+use crate::{
+    serialize::{error::AppError, transaction_comment::TransactionCommentReq},
+    services::transaction_comment::TransactionCommentSrv,
+};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+
+use super::jwt::JwtMiddleware;
+
+async fn request_get_comments(
+    transaction_comment_srv: web::Data<TransactionCommentSrv>,
+    transaction_id: web::Path<String>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match transaction_comment_srv
+        .get_comments(&user_address, &transaction_id)
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_add_comment(
+    transaction_comment_srv: web::Data<TransactionCommentSrv>,
+    transaction_id: web::Path<String>,
+    req: web::Json<TransactionCommentReq>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match transaction_comment_srv
+        .add_comment(&user_address, &transaction_id, req.clone())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_update_comment(
+    transaction_comment_srv: web::Data<TransactionCommentSrv>,
+    path: web::Path<(String, i32)>,
+    req: web::Json<TransactionCommentReq>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    let (transaction_id, comment_id) = path.into_inner();
+    match transaction_comment_srv
+        .update_comment(&user_address, &transaction_id, comment_id, req.clone())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+pub fn route(conf: &mut web::ServiceConfig) {
+    conf.service(
+        web::scope("/comments")
+            .route("/{txId}", web::get().to(request_get_comments))
+            .route("/{txId}", web::post().to(request_add_comment))
+            .route("/{txId}/{id}", web::put().to(request_update_comment)),
+    );
+}
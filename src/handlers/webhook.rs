@@ -0,0 +1,143 @@
+use crate::{
+    serialize::{
+        error::AppError,
+        webhook::{DeliveryFilters, NewWebhookReq, UpdateWebhookReq},
+    },
+    services::webhook::WebhookSrv,
+};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+
+use super::jwt::JwtMiddleware;
+
+async fn request_list_webhooks(
+    webhook_srv: web::Data<WebhookSrv>,
+    multi_sig_address: web::Path<String>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match webhook_srv
+        .list_webhooks(&user_address, &multi_sig_address)
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_create_webhook(
+    webhook_srv: web::Data<WebhookSrv>,
+    multi_sig_address: web::Path<String>,
+    req: web::Json<NewWebhookReq>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match webhook_srv
+        .create_webhook(&user_address, &multi_sig_address, req.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_update_webhook(
+    webhook_srv: web::Data<WebhookSrv>,
+    id: web::Path<i32>,
+    req: web::Json<UpdateWebhookReq>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match webhook_srv
+        .update_webhook(&user_address, id.into_inner(), req.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_delete_webhook(
+    webhook_srv: web::Data<WebhookSrv>,
+    id: web::Path<i32>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match webhook_srv
+        .delete_webhook(&user_address, id.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_list_deliveries(
+    webhook_srv: web::Data<WebhookSrv>,
+    id: web::Path<i32>,
+    filters: web::Query<DeliveryFilters>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    match webhook_srv
+        .list_deliveries(&user_address, id.into_inner(), filters.into_inner())
+        .await
+    {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+async fn request_redeliver(
+    webhook_srv: web::Data<WebhookSrv>,
+    path: web::Path<(i32, i32)>,
+    http_req: HttpRequest,
+    _: JwtMiddleware,
+) -> Result<HttpResponse, AppError> {
+    let user_address = {
+        let ext = http_req.extensions();
+        ext.get::<String>().unwrap().clone()
+    };
+    let (id, delivery_id) = path.into_inner();
+    match webhook_srv.redeliver(&user_address, id, delivery_id).await {
+        Ok(res) => Ok(HttpResponse::Ok().json(res)),
+        Err(err) => Err(err),
+    }
+}
+
+pub fn route(conf: &mut web::ServiceConfig) {
+    conf.service(
+        web::scope("/webhooks")
+            .route("/accounts/{address}", web::get().to(request_list_webhooks))
+            .route(
+                "/accounts/{address}",
+                web::post().to(request_create_webhook),
+            )
+            .route("/{id}", web::put().to(request_update_webhook))
+            .route("/{id}", web::delete().to(request_delete_webhook))
+            .route("/{id}/deliveries", web::get().to(request_list_deliveries))
+            .route(
+                "/{id}/deliveries/{deliveryId}/redeliver",
+                web::post().to(request_redeliver),
+            ),
+    );
+}
@@ -0,0 +1,63 @@
+use chrono::NaiveDateTime;
+use serde_derive::{Deserialize, Serialize};
+use tokio_pg_mapper_derive::PostgresMapper;
+
+pub enum OutboxStatus {
+    Pending,
+    Sent,
+    Failed,
+}
+
+pub const OUTBOX_STATUS_PENDING: i16 = OutboxStatus::Pending as i16;
+pub const OUTBOX_STATUS_SENT: i16 = OutboxStatus::Sent as i16;
+pub const OUTBOX_STATUS_FAILED: i16 = OutboxStatus::Failed as i16;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PostgresMapper)]
+#[pg_mapper(table = "user_emails")]
+pub struct UserEmail {
+    pub user_address: String,
+    pub email: String,
+    pub verified_at: Option<NaiveDateTime>,
+
+    #[serde(skip_serializing)]
+    pub verification_token: Option<String>,
+
+    #[serde(skip_serializing)]
+    pub verification_expires_at: Option<NaiveDateTime>,
+
+    pub created_at: NaiveDateTime,
+
+    #[serde(skip_serializing)]
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PostgresMapper)]
+#[pg_mapper(table = "notification_preferences")]
+pub struct NotificationPreference {
+    pub user_address: String,
+    pub event: String,
+    pub email: bool,
+
+    #[serde(skip_serializing)]
+    pub created_at: NaiveDateTime,
+
+    #[serde(skip_serializing)]
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PostgresMapper)]
+#[pg_mapper(table = "email_outbox")]
+pub struct OutboxEmail {
+    pub id: i32,
+    pub recipient: String,
+    pub subject: String,
+    pub html_body: String,
+    pub text_body: String,
+    pub status: i16,
+    pub attempts: i32,
+    pub next_attempt_at: NaiveDateTime,
+    pub last_error: Option<String>,
+    pub sent_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
@@ -1,5 +1,11 @@
 pub mod address_book;
+pub mod email;
 pub mod multi_sig_account;
 pub mod multi_sig_invite;
+pub mod multi_sig_message;
 pub mod multi_sig_tx;
+pub mod notification;
+pub mod notification_channel;
+pub mod transaction_comment;
 pub mod user;
+pub mod webhook;
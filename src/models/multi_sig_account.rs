@@ -23,6 +23,9 @@ pub struct MultiSigInfo {
     pub signers: i16,
     pub name: String,
     pub multi_sig_witness_data: String,
+    pub fee_rate: i64,
+    pub proposal_validity_hours: Option<i32>,
+    pub auto_broadcast: bool,
 
     #[serde(skip_serializing)]
     pub created_at: NaiveDateTime,
@@ -0,0 +1,41 @@
+use chrono::NaiveDateTime;
+use serde_derive::{Deserialize, Serialize};
+use tokio_pg_mapper_derive::PostgresMapper;
+
+pub enum MessageStatus {
+    Pending,
+    Completed,
+}
+
+pub const MESSAGE_STATUS_PENDING: i16 = MessageStatus::Pending as i16;
+pub const MESSAGE_STATUS_COMPLETED: i16 = MessageStatus::Completed as i16;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PostgresMapper)]
+#[pg_mapper(table = "multi_sig_messages")]
+pub struct MultiSigMessage {
+    pub id: i32,
+    pub multi_sig_address: String,
+    pub proposer_address: String,
+    pub message: String,
+    pub digest: String,
+    pub status: i16,
+    pub proof: Option<String>,
+    pub created_at: NaiveDateTime,
+
+    #[serde(skip_serializing)]
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PostgresMapper)]
+#[pg_mapper(table = "multi_sig_message_signatures")]
+pub struct MultiSigMessageSignature {
+    pub message_id: i32,
+    pub signer_address: String,
+    pub signature: String,
+
+    #[serde(skip_serializing)]
+    pub created_at: NaiveDateTime,
+
+    #[serde(skip_serializing)]
+    pub updated_at: NaiveDateTime,
+}
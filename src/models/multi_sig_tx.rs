@@ -8,6 +8,10 @@ pub enum TransactionStatus {
     Commited,
     Rejected,
     Failed,
+    Superseded,
+    Cancelled,
+    Expired,
+    ReadyToExecute,
 }
 
 pub const TRANSACTION_STATUS_PENDING: i16 = TransactionStatus::Pending as i16;
@@ -15,6 +19,56 @@ pub const TRANSACTION_STATUS_IN_PROGRESSING: i16 = TransactionStatus::InProgress
 pub const TRANSACTION_STATUS_COMMITED: i16 = TransactionStatus::Commited as i16;
 pub const TRANSACTION_STATUS_REJECT: i16 = TransactionStatus::Rejected as i16;
 pub const TRANSACTION_STATUS_FAILED: i16 = TransactionStatus::Failed as i16;
+pub const TRANSACTION_STATUS_SUPERSEDED: i16 = TransactionStatus::Superseded as i16;
+pub const TRANSACTION_STATUS_CANCELLED: i16 = TransactionStatus::Cancelled as i16;
+pub const TRANSACTION_STATUS_EXPIRED: i16 = TransactionStatus::Expired as i16;
+pub const TRANSACTION_STATUS_READY_TO_EXECUTE: i16 = TransactionStatus::ReadyToExecute as i16;
+
+// Proposals which still compete for their inputs
+pub const OPEN_TRANSACTION_STATUSES: [i16; 3] = [
+    TRANSACTION_STATUS_PENDING,
+    TRANSACTION_STATUS_IN_PROGRESSING,
+    TRANSACTION_STATUS_READY_TO_EXECUTE,
+];
+
+// Proposals which have not been broadcast yet
+pub const UNSENT_TRANSACTION_STATUSES: [i16; 2] = [
+    TRANSACTION_STATUS_PENDING,
+    TRANSACTION_STATUS_READY_TO_EXECUTE,
+];
+
+pub fn transaction_status_name(status: i16) -> &'static str {
+    match status {
+        TRANSACTION_STATUS_PENDING => "pending",
+        TRANSACTION_STATUS_IN_PROGRESSING => "in_progress",
+        TRANSACTION_STATUS_COMMITED => "committed",
+        TRANSACTION_STATUS_REJECT => "rejected",
+        TRANSACTION_STATUS_FAILED => "failed",
+        TRANSACTION_STATUS_SUPERSEDED => "superseded",
+        TRANSACTION_STATUS_CANCELLED => "cancelled",
+        TRANSACTION_STATUS_EXPIRED => "expired",
+        TRANSACTION_STATUS_READY_TO_EXECUTE => "ready_to_execute",
+        _ => "unknown",
+    }
+}
+
+pub const TRANSACTION_ACTION_CREATED: &str = "created";
+pub const TRANSACTION_ACTION_SIGNED: &str = "signed";
+pub const TRANSACTION_ACTION_SIGNATURE_REVOKED: &str = "signature_revoked";
+pub const TRANSACTION_ACTION_REJECTED: &str = "rejected";
+pub const TRANSACTION_ACTION_CANCELLED: &str = "cancelled";
+pub const TRANSACTION_ACTION_EXECUTED: &str = "executed";
+pub const TRANSACTION_ACTION_RETRIED: &str = "retried";
+
+// Failure categories of transaction errors
+pub const TRANSACTION_ERROR_NODE_REJECTED: &str = "node_rejected";
+pub const TRANSACTION_ERROR_TRANSPORT: &str = "transport";
+
+// Pre-flight verdicts of the node
+pub const PREFLIGHT_STATUS_PASSED: &str = "passed";
+pub const PREFLIGHT_STATUS_FAILED: &str = "failed";
+pub const PREFLIGHT_STATUS_PENDING_SIGNATURES: &str = "pending_signatures";
+pub const PREFLIGHT_STATUS_UNAVAILABLE: &str = "unavailable";
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PostgresMapper)]
 #[pg_mapper(table = "transactions")]
@@ -23,6 +77,18 @@ pub struct CkbTransaction {
     pub multi_sig_address: String,
     pub payload: String,
     pub status: i16,
+    pub block_number: Option<i64>,
+    pub block_hash: Option<String>,
+    pub confirmations: i64,
+    pub broadcast_at: Option<NaiveDateTime>,
+    pub proposer_address: Option<String>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub status_reason: Option<String>,
+    pub preflight_status: Option<String>,
+    pub preflight_message: Option<String>,
+    pub preflight_at: Option<NaiveDateTime>,
+    pub memo: Option<String>,
+    pub cancels_transaction_id: Option<String>,
 
     #[serde(skip_serializing)]
     pub created_at: NaiveDateTime,
@@ -51,6 +117,7 @@ pub struct TransactionError {
     pub transaction_id: String,
     pub signer_address: String,
     pub error_msg: String,
+    pub category: Option<String>,
 
     #[serde(skip_serializing)]
     pub created_at: NaiveDateTime,
@@ -71,3 +138,30 @@ pub struct TransactionReject {
     #[serde(skip_serializing)]
     pub updated_at: NaiveDateTime,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PostgresMapper)]
+#[pg_mapper(table = "transaction_histories")]
+pub struct TransactionHistory {
+    pub transaction_id: String,
+    pub actor_address: Option<String>,
+    pub action: String,
+    pub created_at: NaiveDateTime,
+
+    #[serde(skip_serializing)]
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PostgresMapper)]
+#[pg_mapper(table = "transaction_labels")]
+pub struct TransactionLabel {
+    pub multi_sig_address: String,
+    pub tx_hash: String,
+    pub category: Option<String>,
+    pub labels: Vec<String>,
+
+    #[serde(skip_serializing)]
+    pub created_at: NaiveDateTime,
+
+    #[serde(skip_serializing)]
+    pub updated_at: NaiveDateTime,
+}
@@ -0,0 +1,19 @@
+use chrono::NaiveDateTime;
+use serde_derive::{Deserialize, Serialize};
+use tokio_pg_mapper_derive::PostgresMapper;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PostgresMapper)]
+#[pg_mapper(table = "notifications")]
+pub struct Notification {
+    pub id: i32,
+    pub user_address: String,
+    pub event: String,
+    pub multi_sig_address: String,
+    pub transaction_id: Option<String>,
+    pub actor_address: Option<String>,
+    pub title: String,
+    pub body: String,
+    pub link: String,
+    pub read_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
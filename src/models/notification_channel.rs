@@ -0,0 +1,25 @@
+use chrono::NaiveDateTime;
+use serde_derive::{Deserialize, Serialize};
+use tokio_pg_mapper_derive::PostgresMapper;
+
+pub const CHANNEL_KIND_TELEGRAM: &str = "telegram";
+pub const CHANNEL_KIND_DISCORD: &str = "discord";
+pub const CHANNEL_KIND_SLACK: &str = "slack";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PostgresMapper)]
+#[pg_mapper(table = "notification_channels")]
+pub struct NotificationChannel {
+    pub id: i32,
+    pub multi_sig_address: String,
+    pub kind: String,
+
+    // discord and slack webhook urls work as bearer secrets, so targets are never shown back
+    #[serde(skip_serializing)]
+    pub target: String,
+
+    pub events: Vec<String>,
+    pub active: bool,
+    pub created_by: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
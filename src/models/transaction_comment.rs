@@ -0,0 +1,17 @@
+use chrono::NaiveDateTime;
+use serde_derive::{Deserialize, Serialize};
+use tokio_pg_mapper_derive::PostgresMapper;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PostgresMapper)]
+#[pg_mapper(table = "transaction_comments")]
+pub struct TransactionComment {
+    pub id: i32,
+    pub transaction_id: String,
+    pub author_address: String,
+    pub body: String,
+    pub edited_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+
+    #[serde(skip_serializing)]
+    pub updated_at: NaiveDateTime,
+}
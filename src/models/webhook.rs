@@ -0,0 +1,54 @@
+use chrono::NaiveDateTime;
+use serde_derive::{Deserialize, Serialize};
+use tokio_pg_mapper_derive::PostgresMapper;
+
+pub enum DeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+pub const DELIVERY_STATUS_PENDING: i16 = DeliveryStatus::Pending as i16;
+pub const DELIVERY_STATUS_DELIVERED: i16 = DeliveryStatus::Delivered as i16;
+pub const DELIVERY_STATUS_FAILED: i16 = DeliveryStatus::Failed as i16;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PostgresMapper)]
+#[pg_mapper(table = "webhooks")]
+pub struct Webhook {
+    pub id: i32,
+    pub multi_sig_address: String,
+    pub url: String,
+
+    // only shown once, when the subscription is created
+    #[serde(skip_serializing)]
+    pub secret: String,
+
+    pub events: Vec<String>,
+    pub active: bool,
+    pub created_by: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PostgresMapper)]
+#[pg_mapper(table = "webhook_deliveries")]
+pub struct WebhookDelivery {
+    pub id: i32,
+    pub webhook_id: i32,
+    pub event: String,
+    pub payload: String,
+    pub status: i16,
+    pub attempts: i32,
+    pub next_attempt_at: NaiveDateTime,
+    pub response_status: Option<i32>,
+
+    // kept for operators, the endpoint's reply isn't shown to account signers
+    #[serde(skip_serializing)]
+    pub response_body: Option<String>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+
+    #[serde(skip_serializing)]
+    pub updated_at: NaiveDateTime,
+}
@@ -3,10 +3,18 @@ use std::str::FromStr;
 use crate::config;
 use crate::serialize::error::AppError;
 use crate::serialize::multi_sig_account::SignerInfo;
-use crate::services::constants::{MAINNET_MULTISIG_CODE_HASH, TESTNET_MULTISIG_CODE_HASH};
+use crate::services::cobuild::WitnessLayout;
+use crate::services::constants::{
+    MAINNET_MULTISIG_CODE_HASH, MAINNET_SUDT_CODE_HASH, MAINNET_XUDT_CODE_HASH,
+    TESTNET_MULTISIG_CODE_HASH, TESTNET_SUDT_CODE_HASH, TESTNET_XUDT_CODE_HASH,
+};
 use crate::services::overrided::OverrideMultisigConfig;
 use anyhow::anyhow;
-use ckb_jsonrpc_types::{CellWithStatus, OutputsValidator, Transaction};
+use ckb_jsonrpc_types::{
+    CellWithStatus, Either, EntryCompleted, EstimateCycles, FeeRateStatistics, JsonBytes,
+    OutputsValidator, Transaction, TransactionWithStatusResponse, TxPoolInfo,
+};
+use ckb_sdk::rpc::ckb_indexer::{Order, Pagination, ScriptType, SearchKey, SearchMode, Tx};
 use ckb_sdk::unlock::{MultisigConfig, ScriptSignError};
 use ckb_sdk::{rpc::CkbRpcClient, NetworkType};
 use ckb_sdk::{Address, RpcError};
@@ -21,6 +29,8 @@ pub const CKB_TESTNET_EXPLORER_API: &str = "https://testnet-api.explorer.nervos.
 pub const CKB_MAINNET_EXPLORER_API: &str = "https://mainnet-api.explorer.nervos.org/api";
 pub const CKB_TESTNET_RPC: &str = "https://testnet.ckb.dev/rpc";
 pub const CKB_MAINNET_RPC: &str = "https://mainnet.ckb.dev/rpc";
+// RPC error code of a transaction rejected by script verification
+pub const RPC_TRANSACTION_FAILED_TO_VERIFY: i64 = -302;
 pub const JOYID_LOCK_SCRIPT_CODE_HASH: &str =
     "d23761b364210735c19c60561d213fb3beae2fd6172743719eff6920e020baac";
 
@@ -61,6 +71,120 @@ pub async fn get_live_cell(
     .unwrap()
 }
 
+pub async fn get_transaction(
+    tx_hash: H256,
+) -> Result<Option<TransactionWithStatusResponse>, ckb_sdk::rpc::RpcError> {
+    let rpc_url: String = get_rpc();
+    tokio::task::spawn_blocking(move || {
+        let client = CkbRpcClient::new(&rpc_url);
+        client.get_transaction(tx_hash)
+    })
+    .await
+    .unwrap()
+}
+
+/// The transaction itself, without its status. `None` when the node doesn't know it.
+pub async fn get_transaction_body(tx_hash: H256) -> Result<Option<Transaction>, RpcError> {
+    Ok(get_transaction(tx_hash)
+        .await?
+        .and_then(|res| res.transaction)
+        .and_then(|res| match res.inner {
+            Either::Left(view) => Some(view.inner),
+            Either::Right(_) => None,
+        }))
+}
+
+pub async fn get_fee_rate_statistics(
+    target: Option<u64>,
+) -> Result<Option<FeeRateStatistics>, ckb_sdk::rpc::RpcError> {
+    let rpc_url: String = get_rpc();
+    tokio::task::spawn_blocking(move || {
+        let client = CkbRpcClient::new(&rpc_url);
+        client.get_fee_rate_statistics(target.map(|target| target.into()))
+    })
+    .await
+    .unwrap()
+}
+
+pub async fn get_tx_pool_info() -> Result<TxPoolInfo, ckb_sdk::rpc::RpcError> {
+    let rpc_url: String = get_rpc();
+    tokio::task::spawn_blocking(move || {
+        let client = CkbRpcClient::new(&rpc_url);
+        client.tx_pool_info()
+    })
+    .await
+    .unwrap()
+}
+
+pub async fn get_tip_block_number() -> Result<u64, ckb_sdk::rpc::RpcError> {
+    let rpc_url: String = get_rpc();
+    tokio::task::spawn_blocking(move || {
+        let client = CkbRpcClient::new(&rpc_url);
+        client.get_tip_block_number().map(|number| number.into())
+    })
+    .await
+    .unwrap()
+}
+
+/// Transactions touching cells locked by `lock`, grouped by transaction, from the node's
+/// built-in indexer.
+pub async fn get_transactions_by_lock(
+    lock: ckb_jsonrpc_types::Script,
+    order: Order,
+    limit: u32,
+    after: Option<JsonBytes>,
+) -> Result<Pagination<Tx>, RpcError> {
+    let rpc_url: String = get_rpc();
+    tokio::task::spawn_blocking(move || {
+        let client = CkbRpcClient::new(&rpc_url);
+        let search_key = SearchKey {
+            script: lock,
+            script_type: ScriptType::Lock,
+            script_search_mode: Some(SearchMode::Exact),
+            filter: None,
+            with_data: None,
+            group_by_transaction: Some(true),
+        };
+        client.get_transactions(search_key, order, limit.into(), after)
+    })
+    .await
+    .unwrap()
+}
+
+/// Block timestamp in milliseconds.
+pub async fn get_block_timestamp(block_number: u64) -> Result<Option<u64>, RpcError> {
+    let rpc_url: String = get_rpc();
+    tokio::task::spawn_blocking(move || {
+        let client = CkbRpcClient::new(&rpc_url);
+        client
+            .get_header_by_number(block_number.into())
+            .map(|header| header.map(|header| header.inner.timestamp.into()))
+    })
+    .await
+    .unwrap()
+}
+
+pub async fn test_tx_pool_accept(tx: Transaction) -> Result<EntryCompleted, RpcError> {
+    let rpc_url: String = get_rpc();
+    tokio::task::spawn_blocking(move || {
+        let client = CkbRpcClient::new(&rpc_url);
+        client.test_tx_pool_accept(tx, None)
+    })
+    .await
+    .unwrap()
+}
+
+/// Run the scripts of a transaction against the current chain state without the pool checks.
+pub async fn estimate_cycles(tx: Transaction) -> Result<EstimateCycles, RpcError> {
+    let rpc_url: String = get_rpc();
+    tokio::task::spawn_blocking(move || {
+        let client = CkbRpcClient::new(&rpc_url);
+        client.estimate_cycles(tx)
+    })
+    .await
+    .unwrap()
+}
+
 pub fn get_ckb_network() -> NetworkType {
     let network: String = config::get("network");
     match network.as_str() {
@@ -90,6 +214,22 @@ pub fn get_multisig_script_hash() -> ckb_types::H256 {
     }
 }
 
+pub fn get_sudt_script_hash() -> ckb_types::H256 {
+    let network: String = config::get("network");
+    match network.as_str() {
+        "mainnet" => MAINNET_SUDT_CODE_HASH,
+        _ => TESTNET_SUDT_CODE_HASH,
+    }
+}
+
+pub fn get_xudt_script_hash() -> ckb_types::H256 {
+    let network: String = config::get("network");
+    match network.as_str() {
+        "mainnet" => MAINNET_XUDT_CODE_HASH,
+        _ => TESTNET_XUDT_CODE_HASH,
+    }
+}
+
 pub fn add_signature_to_witness(
     threshold: usize,
     tx: &TransactionView,
@@ -114,6 +254,14 @@ pub fn add_signature_to_witness(
     // Hardcode input witness idx = 0 while currently we only support simple transfer
     let witness_idx = 0;
     let witness_data = witnesses[witness_idx].raw_data();
+
+    // The multisig lock only reads WitnessArgs, a CoBuild seal would never unlock
+    if WitnessLayout::from_witness(&witness_data).is_some() {
+        return Err(ScriptSignError::Other(anyhow!(
+            "the multisig lock can not be sealed in a CoBuild witness layout"
+        )));
+    }
+
     let mut current_witness: WitnessArgs = if witness_data.is_empty() {
         WitnessArgs::default()
     } else {
@@ -124,17 +272,32 @@ pub fn add_signature_to_witness(
         .to_opt()
         .map(|data| data.raw_data().as_ref().to_vec())
         .unwrap_or(zero_lock);
+    fill_multisig_lock(&mut lock_field, config_data.len(), threshold, signatures)?;
+
+    current_witness = current_witness
+        .as_builder()
+        .lock(Some(Bytes::from(lock_field)).pack())
+        .build();
+    witnesses[witness_idx] = current_witness.as_bytes().pack();
+    Ok(tx.as_advanced_builder().set_witnesses(witnesses).build())
+}
 
-    if lock_field.len() != config_data.len() + threshold * 65 {
+fn fill_multisig_lock(
+    lock_field: &mut [u8],
+    config_len: usize,
+    threshold: usize,
+    signatures: Vec<Bytes>,
+) -> Result<(), ScriptSignError> {
+    if lock_field.len() != config_len + threshold * 65 {
         return Err(ScriptSignError::Other(anyhow!(
             "invalid witness lock field length: {}, expected: {}",
             lock_field.len(),
-            config_data.len() + threshold * 65,
+            config_len + threshold * 65,
         )));
     }
 
     for signature in signatures {
-        let mut idx = config_data.len();
+        let mut idx = config_len;
         while idx < lock_field.len() {
             // Put signature into an empty place.
             if lock_field[idx..idx + 65] == signature {
@@ -149,13 +312,30 @@ pub fn add_signature_to_witness(
             return Err(ScriptSignError::TooManySignatures);
         }
     }
+    Ok(())
+}
 
-    current_witness = current_witness
-        .as_builder()
-        .lock(Some(Bytes::from(lock_field)).pack())
-        .build();
-    witnesses[witness_idx] = current_witness.as_bytes().pack();
-    Ok(tx.as_advanced_builder().set_witnesses(witnesses).build())
+/// Parse the multisig config stored as `multi_sig_witness_data`:
+/// `S | R | M | N | blake160(pubkey) * N`.
+pub fn parse_multisig_config(multi_sig_witness_data: &str) -> Result<MultisigConfig, AppError> {
+    let config_data = hex::decode(multi_sig_witness_data).map_err(|err| {
+        AppError::new(500)
+            .cause(err)
+            .message("invalid multisig config")
+    })?;
+    if config_data.len() < 4 || config_data.len() != 4 + 20 * config_data[3] as usize {
+        return Err(AppError::new(500).message("invalid multisig config"));
+    }
+
+    let sighash_addresses = config_data[4..]
+        .chunks(20)
+        .map(|hash| H160::from_slice(hash).unwrap())
+        .collect();
+    MultisigConfig::new_with(sighash_addresses, config_data[1], config_data[2]).map_err(|err| {
+        AppError::new(500)
+            .cause(err)
+            .message("invalid multisig config")
+    })
 }
 
 pub fn get_multisig_config(
@@ -0,0 +1,250 @@
+use std::sync::Arc;
+
+use chrono::NaiveDateTime;
+use deadpool_postgres::{Client, Pool, PoolError};
+use tokio_pg_mapper::FromTokioPostgresRow;
+
+use crate::models::email::{
+    NotificationPreference, OutboxEmail, UserEmail, OUTBOX_STATUS_FAILED, OUTBOX_STATUS_PENDING,
+    OUTBOX_STATUS_SENT,
+};
+
+#[derive(Clone, Debug)]
+pub struct EmailDao {
+    db: Arc<Pool>,
+}
+
+impl EmailDao {
+    pub fn new(db: Arc<Pool>) -> Self {
+        EmailDao { db: db.clone() }
+    }
+
+    pub async fn get_email(&self, user_address: &String) -> Result<Option<UserEmail>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "SELECT * FROM user_emails WHERE user_address=$1;";
+        let row = client.query_opt(stmt, &[user_address]).await?;
+        Ok(row.map(|row| UserEmail::from_row_ref(&row).unwrap()))
+    }
+
+    /// Set the email of a user, unverified until the token comes back.
+    pub async fn upsert_email(
+        &self,
+        user_address: &String,
+        email: &String,
+        verification_token: &String,
+        verification_expires_at: &NaiveDateTime,
+    ) -> Result<UserEmail, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "INSERT INTO user_emails
+                (user_address, email, verification_token, verification_expires_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_address) DO UPDATE
+            SET email=$2, verified_at=NULL, verification_token=$3, verification_expires_at=$4,
+                updated_at=NOW()
+            RETURNING *;";
+        let row = client
+            .query_one(
+                stmt,
+                &[
+                    user_address,
+                    email,
+                    verification_token,
+                    verification_expires_at,
+                ],
+            )
+            .await?;
+        Ok(UserEmail::from_row(row).unwrap())
+    }
+
+    /// Returns `None` when the token is wrong or expired.
+    pub async fn verify_email(
+        &self,
+        user_address: &String,
+        verification_token: &String,
+    ) -> Result<Option<UserEmail>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "UPDATE user_emails
+            SET verified_at=NOW(), verification_token=NULL, verification_expires_at=NULL,
+                updated_at=NOW()
+            WHERE user_address=$1 AND verification_token=$2 AND verification_expires_at>NOW()
+            RETURNING *;";
+        let row = client
+            .query_opt(stmt, &[user_address, verification_token])
+            .await?;
+        Ok(row.map(|row| UserEmail::from_row_ref(&row).unwrap()))
+    }
+
+    pub async fn delete_email(&self, user_address: &String) -> Result<bool, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "DELETE FROM user_emails WHERE user_address=$1;";
+        let deleted = client.execute(stmt, &[user_address]).await?;
+        Ok(deleted > 0)
+    }
+
+    pub async fn get_preferences(
+        &self,
+        user_address: &String,
+    ) -> Result<Vec<NotificationPreference>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let _stmt = "SELECT * FROM notification_preferences WHERE user_address=$1;";
+        let stmt = client.prepare(_stmt).await?;
+
+        let preferences = client
+            .query(&stmt, &[user_address])
+            .await?
+            .iter()
+            .map(|row| NotificationPreference::from_row_ref(row).unwrap())
+            .collect::<Vec<NotificationPreference>>();
+
+        Ok(preferences)
+    }
+
+    pub async fn set_preference(
+        &self,
+        user_address: &String,
+        event: &String,
+        email: bool,
+    ) -> Result<(), PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "INSERT INTO notification_preferences (user_address, event, email)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_address, event) DO UPDATE SET email=$3, updated_at=NOW();";
+        client.execute(stmt, &[user_address, event, &email]).await?;
+        Ok(())
+    }
+
+    /// Verified emails of the signers of an account who want the event, but the actor.
+    pub async fn get_account_recipients(
+        &self,
+        multi_sig_address: &String,
+        event: &String,
+        actor_address: &Option<String>,
+        enabled_by_default: bool,
+    ) -> Result<Vec<UserEmail>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let _stmt = "SELECT ue.* FROM user_emails ue
+            INNER JOIN multi_sig_signers mss ON mss.signer_address = ue.user_address
+            LEFT JOIN notification_preferences np
+                ON np.user_address = ue.user_address AND np.event = $2
+            WHERE mss.multi_sig_address=$1 AND ue.verified_at IS NOT NULL
+                AND ue.user_address IS DISTINCT FROM $3 AND COALESCE(np.email, $4);";
+        let stmt = client.prepare(_stmt).await?;
+
+        let recipients = client
+            .query(
+                &stmt,
+                &[multi_sig_address, event, actor_address, &enabled_by_default],
+            )
+            .await?
+            .iter()
+            .map(|row| UserEmail::from_row_ref(row).unwrap())
+            .collect::<Vec<UserEmail>>();
+
+        Ok(recipients)
+    }
+
+    /// Verified email of a single user, if they want the event.
+    pub async fn get_user_recipient(
+        &self,
+        user_address: &String,
+        event: &String,
+        enabled_by_default: bool,
+    ) -> Result<Option<UserEmail>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "SELECT ue.* FROM user_emails ue
+            LEFT JOIN notification_preferences np
+                ON np.user_address = ue.user_address AND np.event = $2
+            WHERE ue.user_address=$1 AND ue.verified_at IS NOT NULL
+                AND COALESCE(np.email, $3);";
+        let row = client
+            .query_opt(stmt, &[user_address, event, &enabled_by_default])
+            .await?;
+        Ok(row.map(|row| UserEmail::from_row_ref(&row).unwrap()))
+    }
+
+    pub async fn enqueue_email(
+        &self,
+        recipient: &String,
+        subject: &String,
+        html_body: &String,
+        text_body: &String,
+    ) -> Result<(), PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "INSERT INTO email_outbox (recipient, subject, html_body, text_body)
+            VALUES ($1, $2, $3, $4);";
+        client
+            .execute(stmt, &[recipient, subject, html_body, text_body])
+            .await?;
+        Ok(())
+    }
+
+    /// Take the due emails off the outbox, leased for `lease_secs` like webhook deliveries.
+    pub async fn claim_due_emails(
+        &self,
+        limit: i64,
+        lease_secs: i32,
+    ) -> Result<Vec<OutboxEmail>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let _stmt = "UPDATE email_outbox
+            SET next_attempt_at=NOW() + ($2::INTEGER * INTERVAL '1 second'), updated_at=NOW()
+            WHERE id IN (
+                SELECT id FROM email_outbox
+                WHERE status=$1 AND next_attempt_at<=NOW()
+                ORDER BY next_attempt_at ASC LIMIT $3
+                FOR UPDATE SKIP LOCKED
+            ) RETURNING *;";
+        let stmt = client.prepare(_stmt).await?;
+
+        let emails = client
+            .query(&stmt, &[&OUTBOX_STATUS_PENDING, &lease_secs, &limit])
+            .await?
+            .iter()
+            .map(|row| OutboxEmail::from_row_ref(row).unwrap())
+            .collect::<Vec<OutboxEmail>>();
+
+        Ok(emails)
+    }
+
+    pub async fn mark_sent(&self, id: i32) -> Result<(), PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "UPDATE email_outbox
+            SET status=$1, attempts=attempts + 1, last_error=NULL, sent_at=NOW(), updated_at=NOW()
+            WHERE id=$2;";
+        client.execute(stmt, &[&OUTBOX_STATUS_SENT, &id]).await?;
+        Ok(())
+    }
+
+    /// Record a failed attempt. Without `retry_in_secs` the email gives up.
+    pub async fn mark_attempt_failed(
+        &self,
+        id: i32,
+        error: &String,
+        retry_in_secs: Option<i32>,
+    ) -> Result<(), PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let status = match retry_in_secs {
+            Some(_) => OUTBOX_STATUS_PENDING,
+            None => OUTBOX_STATUS_FAILED,
+        };
+        let stmt = "UPDATE email_outbox
+            SET status=$1, attempts=attempts + 1, last_error=$2, updated_at=NOW(),
+                next_attempt_at=NOW() + (COALESCE($3::INTEGER, 0) * INTERVAL '1 second')
+            WHERE id=$4;";
+        client
+            .execute(stmt, &[&status, error, &retry_in_secs, &id])
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use deadpool_postgres::{Client, Pool, PoolError};
+
+#[derive(Clone, Debug)]
+pub struct EventDao {
+    db: Arc<Pool>,
+}
+
+impl EventDao {
+    pub fn new(db: Arc<Pool>) -> Self {
+        EventDao { db: db.clone() }
+    }
+
+    /// Broadcast a payload to every connection listening on the channel, on all replicas.
+    pub async fn notify(&self, channel: &str, payload: &String) -> Result<(), PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "SELECT pg_notify($1, $2);";
+        client.execute(stmt, &[&channel, payload]).await?;
+        Ok(())
+    }
+}
@@ -4,15 +4,23 @@ use crate::{
     models::{
         multi_sig_account::{MultiSigInfo, MultiSigSigner},
         multi_sig_invite::MultiSigInvite,
-        multi_sig_tx::{CkbSignature, CkbTransaction, TransactionError, TransactionReject},
+        multi_sig_tx::{
+            CkbSignature, CkbTransaction, TransactionError, TransactionHistory, TransactionLabel,
+            TransactionReject, TRANSACTION_ACTION_CREATED, TRANSACTION_ACTION_SIGNATURE_REVOKED,
+            TRANSACTION_STATUS_CANCELLED, TRANSACTION_STATUS_EXPIRED, TRANSACTION_STATUS_FAILED,
+            TRANSACTION_STATUS_PENDING, TRANSACTION_STATUS_SUPERSEDED, UNSENT_TRANSACTION_STATUSES,
+        },
     },
     serialize::{
         error::AppError,
-        multi_sig_account::{MultiSigAccountUpdateReq, NewMultiSigAccountReq, TransactionFilters},
+        multi_sig_account::{
+            MultiSigAccountUpdateReq, NewMultiSigAccountReq, NewTransferReq, TransactionFilters,
+        },
     },
+    services::constants::DEFAULT_FEE_RATE,
 };
-use chrono::Utc;
-use deadpool_postgres::{Client, Pool, PoolError, Transaction};
+use chrono::{NaiveDateTime, Utc};
+use deadpool_postgres::{Client, GenericClient, Pool, PoolError, Transaction};
 use tokio_pg_mapper::FromTokioPostgresRow;
 
 #[derive(Clone, Debug)]
@@ -157,8 +165,14 @@ impl MultiSigDao {
         let mut _stmt = "SELECT tx.* FROM transactions tx
             LEFT JOIN multi_sig_signers mss
                 ON mss.multi_sig_address = tx.multi_sig_address
-            WHERE mss.signer_address=$1 and tx.multi_sig_address=$2"
-            .to_string();
+            WHERE mss.signer_address=$1 and tx.multi_sig_address=$2
+                AND ($3::TEXT IS NULL OR EXISTS (
+                    SELECT 1 FROM transaction_labels tl
+                    WHERE tl.multi_sig_address = tx.multi_sig_address
+                        AND tl.tx_hash = tx.transaction_id
+                        AND lower($3) = ANY(tl.labels)
+                ))"
+        .to_string();
 
         if let Some(status) = filters.status {
             let statuses: Vec<&str> = status.split(',').collect();
@@ -169,12 +183,21 @@ impl MultiSigDao {
             _stmt = format!("{} AND tx.transaction_id='{}'", _stmt, hash);
         }
 
-        _stmt = format!("{} ORDER BY tx.created_at DESC OFFSET $3 LIMIT $4", _stmt);
+        _stmt = format!("{} ORDER BY tx.created_at DESC OFFSET $4 LIMIT $5", _stmt);
 
         let stmt = client.prepare(&_stmt).await?;
 
         let txs = client
-            .query(&stmt, &[&user_address, &multisig_address, &offset, &limit])
+            .query(
+                &stmt,
+                &[
+                    &user_address,
+                    &multisig_address,
+                    &filters.label,
+                    &offset,
+                    &limit,
+                ],
+            )
             .await?
             .iter()
             .map(|row| CkbTransaction::from_row_ref(row).unwrap())
@@ -194,8 +217,14 @@ impl MultiSigDao {
         let mut _stmt = "SELECT COUNT(*) as total_record FROM transactions tx
             LEFT JOIN multi_sig_signers mss
                 ON mss.multi_sig_address = tx.multi_sig_address
-            WHERE mss.signer_address=$1 and tx.multi_sig_address=$2"
-            .to_string();
+            WHERE mss.signer_address=$1 and tx.multi_sig_address=$2
+                AND ($3::TEXT IS NULL OR EXISTS (
+                    SELECT 1 FROM transaction_labels tl
+                    WHERE tl.multi_sig_address = tx.multi_sig_address
+                        AND tl.tx_hash = tx.transaction_id
+                        AND lower($3) = ANY(tl.labels)
+                ))"
+        .to_string();
 
         if let Some(status) = filters.status {
             let statuses: Vec<&str> = status.split(',').collect();
@@ -209,13 +238,41 @@ impl MultiSigDao {
         let stmt = client.prepare(&_stmt).await?;
 
         let row = client
-            .query_one(&stmt, &[&user_address, &multisig_address])
+            .query_one(&stmt, &[&user_address, &multisig_address, &filters.label])
             .await
             .unwrap();
 
         Ok(row.get(0))
     }
 
+    /// Proposals of an account created within `[from, to)`, oldest first.
+    pub async fn get_transactions_for_export(
+        &self,
+        multisig_address: &str,
+        from: &Option<NaiveDateTime>,
+        to: &Option<NaiveDateTime>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<CkbTransaction>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let _stmt = "SELECT * FROM transactions
+            WHERE multi_sig_address=$1
+                AND ($2::TIMESTAMP IS NULL OR created_at >= $2)
+                AND ($3::TIMESTAMP IS NULL OR created_at < $3)
+            ORDER BY created_at ASC, transaction_id ASC OFFSET $4 LIMIT $5;";
+        let stmt = client.prepare(_stmt).await?;
+
+        let txs = client
+            .query(&stmt, &[&multisig_address, from, to, &offset, &limit])
+            .await?
+            .iter()
+            .map(|row| CkbTransaction::from_row_ref(row).unwrap())
+            .collect::<Vec<CkbTransaction>>();
+
+        Ok(txs)
+    }
+
     pub async fn create_new_account(
         &self,
         tx: &Transaction<'_>,
@@ -242,6 +299,9 @@ impl MultiSigDao {
             signers: req.signers.len() as i16,
             name: req.name.clone(),
             multi_sig_witness_data: multi_sig_witness_data.clone(),
+            fee_rate: DEFAULT_FEE_RATE,
+            proposal_validity_hours: None,
+            auto_broadcast: true,
             created_at: Utc::now().naive_utc(),
             updated_at: Utc::now().naive_utc(),
         })
@@ -249,9 +309,23 @@ impl MultiSigDao {
 
     pub async fn update_account(&self, req: MultiSigAccountUpdateReq) -> Result<bool, PoolError> {
         let client: Client = self.db.get().await?;
-        let stmt = "UPDATE multi_sig_info SET name = $1 WHERE multi_sig_address = $2";
+        let stmt = "UPDATE multi_sig_info
+            SET name = $1,
+                fee_rate = COALESCE($3, fee_rate),
+                proposal_validity_hours = CASE WHEN $4::INTEGER IS NULL THEN proposal_validity_hours ELSE NULLIF($4, 0) END,
+                auto_broadcast = COALESCE($5, auto_broadcast)
+            WHERE multi_sig_address = $2";
         let res = client
-            .execute(stmt, &[&req.name, &req.multi_sig_address])
+            .execute(
+                stmt,
+                &[
+                    &req.name,
+                    &req.multi_sig_address,
+                    &req.fee_rate,
+                    &req.proposal_validity_hours,
+                    &req.auto_broadcast,
+                ],
+            )
             .await?;
         Ok(res > 0)
     }
@@ -260,9 +334,10 @@ impl MultiSigDao {
         &self,
         multi_sig_address: &String,
         transaction_id: &String,
-        payload: &String,
+        inputs: &[(String, i32)],
+        expires_at: &Option<NaiveDateTime>,
         signer_address: &String,
-        signature: &String,
+        req: &NewTransferReq,
     ) -> Result<CkbTransaction, PoolError> {
         let mut client: Client = self.db.get().await?;
 
@@ -270,30 +345,51 @@ impl MultiSigDao {
 
         // Create tx
         let _stmt =
-            "INSERT INTO transactions (transaction_id, multi_sig_address, payload, status) VALUES ($1, $2, $3, 0);";
+            "INSERT INTO transactions (transaction_id, multi_sig_address, payload, status, proposer_address, expires_at, memo) VALUES ($1, $2, $3, 0, $4, $5, $6) RETURNING *;";
         let stmt = db_transaction.prepare(_stmt).await?;
-        db_transaction
-            .execute(&stmt, &[transaction_id, multi_sig_address, payload])
-            .await?;
+        let ckb_tx = db_transaction
+            .query_one(
+                &stmt,
+                &[
+                    transaction_id,
+                    multi_sig_address,
+                    &req.payload,
+                    signer_address,
+                    expires_at,
+                    &req.memo,
+                ],
+            )
+            .await
+            .map(|row| CkbTransaction::from_row(row).unwrap())?;
+
+        // Store spent outpoints to detect conflicts between proposals
+        let _stmt = "INSERT INTO transaction_inputs (transaction_id, tx_hash, output_index) VALUES ($1, $2, $3);";
+        let stmt = db_transaction.prepare(_stmt).await?;
+        for (tx_hash, output_index) in inputs {
+            db_transaction
+                .execute(&stmt, &[transaction_id, tx_hash, output_index])
+                .await?;
+        }
 
         // Add first signatures - requester of this new transaction
         let _stmt =
             "INSERT INTO signatures (signer_address, transaction_id, signature) VALUES ($1, $2, $3);";
         let stmt = db_transaction.prepare(_stmt).await?;
         db_transaction
-            .execute(&stmt, &[signer_address, transaction_id, &signature])
+            .execute(&stmt, &[signer_address, transaction_id, &req.signature])
             .await?;
 
+        Self::insert_history(
+            &db_transaction,
+            transaction_id,
+            signer_address,
+            TRANSACTION_ACTION_CREATED,
+        )
+        .await?;
+
         db_transaction.commit().await?;
 
-        Ok(CkbTransaction {
-            transaction_id: transaction_id.clone(),
-            multi_sig_address: multi_sig_address.clone(),
-            payload: payload.clone(),
-            status: 0,
-            created_at: Utc::now().naive_utc(),
-            updated_at: Utc::now().naive_utc(),
-        })
+        Ok(ckb_tx)
     }
 
     pub async fn get_tx_by_hash(&self, txid: &String) -> Result<Option<CkbTransaction>, PoolError> {
@@ -328,8 +424,6 @@ impl MultiSigDao {
     pub async fn add_signature(
         &self,
         transaction_id: &String,
-        multi_sig_address: &str,
-        payload: &str,
         signer_address: &String,
         signature: &String,
     ) -> Result<CkbTransaction, PoolError> {
@@ -343,14 +437,11 @@ impl MultiSigDao {
             .execute(&stmt, &[signer_address, transaction_id, &signature])
             .await?;
 
-        Ok(CkbTransaction {
-            transaction_id: transaction_id.clone(),
-            multi_sig_address: multi_sig_address.to_owned(),
-            payload: payload.to_owned(),
-            status: 0,
-            created_at: Utc::now().naive_utc(),
-            updated_at: Utc::now().naive_utc(),
-        })
+        let _stmt = "SELECT * FROM transactions WHERE transaction_id=$1;";
+        let stmt = client.prepare(_stmt).await?;
+        let row = client.query_one(&stmt, &[transaction_id]).await?;
+
+        Ok(CkbTransaction::from_row(row).unwrap())
     }
 
     pub async fn get_matched_signer(
@@ -401,7 +492,7 @@ impl MultiSigDao {
         let db_transaction = client.transaction().await?;
 
         // Update tx
-        let _stmt = "UPDATE transactions SET status = 1, payload = $2  WHERE transaction_id = $1 RETURNING *;";
+        let _stmt = "UPDATE transactions SET status = 1, payload = $2, broadcast_at = NOW() WHERE transaction_id = $1 RETURNING *;";
         let stmt = db_transaction.prepare(_stmt).await?;
         let tx_updated = db_transaction
             .query_one(&stmt, &[transaction_id, payload])
@@ -412,6 +503,369 @@ impl MultiSigDao {
         Ok(tx_updated)
     }
 
+    pub async fn get_txs_by_status(&self, status: i16) -> Result<Vec<CkbTransaction>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let _stmt = "SELECT * FROM transactions WHERE status=$1 ORDER BY created_at ASC;";
+        let stmt = client.prepare(_stmt).await?;
+
+        let txs = client
+            .query(&stmt, &[&status])
+            .await?
+            .iter()
+            .map(|row| CkbTransaction::from_row_ref(row).unwrap())
+            .collect::<Vec<CkbTransaction>>();
+
+        Ok(txs)
+    }
+
+    /// Failed transactions whose latest error is of `category` and which are due for
+    /// another broadcast. The delay doubles with each error of the category and the
+    /// transaction is given up after `max_attempts` of them.
+    pub async fn get_txs_due_for_rebroadcast(
+        &self,
+        category: &String,
+        max_attempts: i64,
+        backoff_secs: i32,
+    ) -> Result<Vec<CkbTransaction>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let _stmt = "
+            SELECT t.* FROM transactions t
+            JOIN (
+                SELECT transaction_id, COUNT(*) AS attempts, MAX(created_at) AS last_error_at
+                FROM transaction_errors WHERE category=$2 GROUP BY transaction_id
+            ) e ON e.transaction_id=t.transaction_id
+            WHERE t.status=$1 AND e.attempts < $3
+                AND NOT EXISTS (
+                    SELECT 1 FROM transaction_errors l
+                    WHERE l.transaction_id=t.transaction_id AND l.created_at > e.last_error_at
+                )
+                AND e.last_error_at
+                    + ($4::INTEGER * POWER(2, e.attempts - 1) * INTERVAL '1 second') <= NOW()
+            ORDER BY t.created_at ASC;";
+        let stmt = client.prepare(_stmt).await?;
+
+        let txs = client
+            .query(
+                &stmt,
+                &[
+                    &TRANSACTION_STATUS_FAILED,
+                    category,
+                    &max_attempts,
+                    &backoff_secs,
+                ],
+            )
+            .await?
+            .iter()
+            .map(|row| CkbTransaction::from_row_ref(row).unwrap())
+            .collect::<Vec<CkbTransaction>>();
+
+        Ok(txs)
+    }
+
+    pub async fn update_transaction_block(
+        &self,
+        transaction_id: &String,
+        status: i16,
+        block_number: i64,
+        block_hash: &String,
+        confirmations: i64,
+    ) -> Result<bool, PoolError> {
+        let client: Client = self.db.get().await?;
+        let stmt = "UPDATE transactions
+            SET status=$1, block_number=$2, block_hash=$3, confirmations=$4, updated_at=NOW()
+            WHERE transaction_id=$5";
+        let res = client
+            .execute(
+                stmt,
+                &[
+                    &status,
+                    &block_number,
+                    block_hash,
+                    &confirmations,
+                    transaction_id,
+                ],
+            )
+            .await?;
+        Ok(res > 0)
+    }
+
+    async fn lock_transaction(
+        tx: &Transaction<'_>,
+        transaction_id: &String,
+    ) -> Result<Option<CkbTransaction>, PoolError> {
+        let stmt = "SELECT * FROM transactions WHERE transaction_id=$1 FOR UPDATE;";
+        let row = tx.query_opt(stmt, &[transaction_id]).await?;
+        Ok(row.map(|row| CkbTransaction::from_row_ref(&row).unwrap()))
+    }
+
+    async fn count_signatures(
+        tx: &Transaction<'_>,
+        transaction_id: &String,
+    ) -> Result<i64, PoolError> {
+        let stmt = "SELECT COUNT(*) FROM signatures WHERE transaction_id=$1;";
+        let row = tx.query_one(stmt, &[transaction_id]).await?;
+        Ok(row.get(0))
+    }
+
+    /// Move a transaction which has reached its threshold from `from_status` to `to_status`
+    /// and return its signatures. Returns `None` when it is no longer in `from_status` or
+    /// below threshold.
+    pub async fn claim_signed_transaction(
+        &self,
+        transaction_id: &String,
+        threshold: i16,
+        from_status: i16,
+        to_status: i16,
+    ) -> Result<Option<Vec<CkbSignature>>, PoolError> {
+        let mut client: Client = self.db.get().await?;
+        let db_transaction = client.transaction().await?;
+
+        let transaction = Self::lock_transaction(&db_transaction, transaction_id).await?;
+        if transaction.map(|tx| tx.status) != Some(from_status) {
+            return Ok(None);
+        }
+
+        let stmt = "SELECT * FROM signatures WHERE transaction_id=$1 ORDER BY created_at ASC;";
+        let signatures = db_transaction
+            .query(stmt, &[transaction_id])
+            .await?
+            .iter()
+            .map(|row| CkbSignature::from_row_ref(row).unwrap())
+            .collect::<Vec<CkbSignature>>();
+        if signatures.len() < threshold as usize {
+            return Ok(None);
+        }
+
+        let stmt = "UPDATE transactions SET status=$1, updated_at=NOW() WHERE transaction_id=$2";
+        db_transaction
+            .execute(stmt, &[&to_status, transaction_id])
+            .await?;
+
+        db_transaction.commit().await?;
+        Ok(Some(signatures))
+    }
+
+    /// Delete the signer's signature while the transaction is still pending and below
+    /// threshold. The transaction row is locked so it can not race `claim_signed_transaction`.
+    pub async fn revoke_signature(
+        &self,
+        transaction_id: &String,
+        signer_address: &String,
+        threshold: i16,
+    ) -> Result<bool, PoolError> {
+        let mut client: Client = self.db.get().await?;
+        let db_transaction = client.transaction().await?;
+
+        let transaction = Self::lock_transaction(&db_transaction, transaction_id).await?;
+        if transaction.map(|tx| tx.status) != Some(TRANSACTION_STATUS_PENDING) {
+            return Ok(false);
+        }
+
+        if Self::count_signatures(&db_transaction, transaction_id).await? >= threshold as i64 {
+            return Ok(false);
+        }
+
+        let stmt = "DELETE FROM signatures WHERE transaction_id=$1 AND signer_address=$2";
+        let res = db_transaction
+            .execute(stmt, &[transaction_id, signer_address])
+            .await?;
+        if res == 0 {
+            return Ok(false);
+        }
+
+        Self::insert_history(
+            &db_transaction,
+            transaction_id,
+            signer_address,
+            TRANSACTION_ACTION_SIGNATURE_REVOKED,
+        )
+        .await?;
+
+        db_transaction.commit().await?;
+        Ok(true)
+    }
+
+    pub async fn update_preflight(
+        &self,
+        transaction_id: &String,
+        status: &str,
+        message: &Option<String>,
+    ) -> Result<Option<CkbTransaction>, PoolError> {
+        let client: Client = self.db.get().await?;
+        let stmt = "UPDATE transactions
+            SET preflight_status=$1, preflight_message=$2, preflight_at=NOW(), updated_at=NOW()
+            WHERE transaction_id=$3 RETURNING *;";
+        let row = client
+            .query_opt(stmt, &[&status, message, transaction_id])
+            .await?;
+        Ok(row.map(|row| CkbTransaction::from_row_ref(&row).unwrap()))
+    }
+
+    // History
+
+    async fn insert_history(
+        client: &impl GenericClient,
+        transaction_id: &String,
+        actor_address: &String,
+        action: &str,
+    ) -> Result<(), PoolError> {
+        let stmt = "INSERT INTO transaction_histories (transaction_id, actor_address, action) VALUES ($1, $2, $3);";
+        client
+            .execute(stmt, &[transaction_id, actor_address, &action])
+            .await?;
+        Ok(())
+    }
+
+    pub async fn add_history(
+        &self,
+        transaction_id: &String,
+        actor_address: &String,
+        action: &str,
+    ) -> Result<(), PoolError> {
+        let client: Client = self.db.get().await?;
+        Self::insert_history(&client, transaction_id, actor_address, action).await
+    }
+
+    pub async fn get_histories_by_txid(
+        &self,
+        transaction_id: &String,
+    ) -> Result<Vec<TransactionHistory>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let _stmt =
+            "SELECT * FROM transaction_histories WHERE transaction_id=$1 ORDER BY created_at ASC, id ASC;";
+        let stmt = client.prepare(_stmt).await?;
+
+        let histories = client
+            .query(&stmt, &[transaction_id])
+            .await?
+            .iter()
+            .map(|row| TransactionHistory::from_row_ref(row).unwrap())
+            .collect::<Vec<TransactionHistory>>();
+
+        Ok(histories)
+    }
+
+    // Labels
+
+    pub async fn get_label(
+        &self,
+        multi_sig_address: &String,
+        tx_hash: &String,
+    ) -> Result<Option<TransactionLabel>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "SELECT * FROM transaction_labels WHERE multi_sig_address=$1 AND tx_hash=$2;";
+        let row = client
+            .query_opt(stmt, &[multi_sig_address, tx_hash])
+            .await?;
+        Ok(row.map(|row| TransactionLabel::from_row_ref(&row).unwrap()))
+    }
+
+    pub async fn get_labels_by_multisig(
+        &self,
+        multi_sig_address: &String,
+    ) -> Result<Vec<TransactionLabel>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let _stmt =
+            "SELECT * FROM transaction_labels WHERE multi_sig_address=$1 ORDER BY updated_at DESC;";
+        let stmt = client.prepare(_stmt).await?;
+
+        let labels = client
+            .query(&stmt, &[multi_sig_address])
+            .await?
+            .iter()
+            .map(|row| TransactionLabel::from_row_ref(row).unwrap())
+            .collect::<Vec<TransactionLabel>>();
+
+        Ok(labels)
+    }
+
+    pub async fn upsert_label(
+        &self,
+        multi_sig_address: &String,
+        tx_hash: &String,
+        labels: &[String],
+        category: &Option<String>,
+    ) -> Result<TransactionLabel, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "INSERT INTO transaction_labels (multi_sig_address, tx_hash, labels, category)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (multi_sig_address, tx_hash)
+            DO UPDATE SET labels = EXCLUDED.labels, category = EXCLUDED.category, updated_at = NOW()
+            RETURNING *;";
+        let row = client
+            .query_one(stmt, &[multi_sig_address, tx_hash, &labels, category])
+            .await?;
+        Ok(TransactionLabel::from_row(row).unwrap())
+    }
+
+    // Conflicts
+
+    pub async fn get_conflicting_txids(
+        &self,
+        transaction_id: &String,
+        statuses: &[i16],
+    ) -> Result<Vec<String>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let _stmt = "SELECT DISTINCT other.transaction_id
+            FROM transaction_inputs ti
+            INNER JOIN transaction_inputs other
+                ON other.tx_hash = ti.tx_hash
+                AND other.output_index = ti.output_index
+                AND other.transaction_id <> ti.transaction_id
+            INNER JOIN transactions tx ON tx.transaction_id = other.transaction_id
+            WHERE ti.transaction_id=$1 AND tx.status = ANY($2);";
+        let stmt = client.prepare(_stmt).await?;
+
+        let txids = client
+            .query(&stmt, &[transaction_id, &statuses])
+            .await?
+            .iter()
+            .map(|row| row.get(0))
+            .collect::<Vec<String>>();
+
+        Ok(txids)
+    }
+
+    pub async fn supersede_conflicting_txs(
+        &self,
+        transaction_id: &String,
+        statuses: &[i16],
+    ) -> Result<Vec<String>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let _stmt = "UPDATE transactions SET status=$2, updated_at=NOW()
+            WHERE status = ANY($3) AND transaction_id IN (
+                SELECT other.transaction_id
+                FROM transaction_inputs ti
+                INNER JOIN transaction_inputs other
+                    ON other.tx_hash = ti.tx_hash
+                    AND other.output_index = ti.output_index
+                    AND other.transaction_id <> ti.transaction_id
+                WHERE ti.transaction_id=$1
+            )
+            RETURNING transaction_id;";
+        let stmt = client.prepare(_stmt).await?;
+
+        let txids = client
+            .query(
+                &stmt,
+                &[transaction_id, &TRANSACTION_STATUS_SUPERSEDED, &statuses],
+            )
+            .await?
+            .iter()
+            .map(|row| row.get(0))
+            .collect::<Vec<String>>();
+
+        Ok(txids)
+    }
+
     // Invite
 
     pub async fn get_invites_list(&self, address: &String) -> Result<Vec<MultiSigInfo>, PoolError> {
@@ -545,6 +999,97 @@ impl MultiSigDao {
         Ok(transactions)
     }
 
+    /// Cancel the transaction while it is still pending and below threshold. The transaction
+    /// row is locked so it can not race `claim_signed_transaction`.
+    pub async fn cancel_transaction(
+        &self,
+        transaction_id: &String,
+        threshold: i16,
+    ) -> Result<bool, PoolError> {
+        let mut client: Client = self.db.get().await?;
+        let db_transaction = client.transaction().await?;
+
+        let transaction = Self::lock_transaction(&db_transaction, transaction_id).await?;
+        if transaction.map(|tx| tx.status) != Some(TRANSACTION_STATUS_PENDING) {
+            return Ok(false);
+        }
+
+        if Self::count_signatures(&db_transaction, transaction_id).await? >= threshold as i64 {
+            return Ok(false);
+        }
+
+        let stmt = "UPDATE transactions SET status=$1, updated_at=NOW() WHERE transaction_id=$2";
+        db_transaction
+            .execute(stmt, &[&TRANSACTION_STATUS_CANCELLED, transaction_id])
+            .await?;
+
+        db_transaction.commit().await?;
+        Ok(true)
+    }
+
+    pub async fn link_cancellation(
+        &self,
+        transaction_id: &String,
+        cancels_transaction_id: &String,
+    ) -> Result<Option<CkbTransaction>, PoolError> {
+        let client: Client = self.db.get().await?;
+        let stmt = "UPDATE transactions SET cancels_transaction_id=$2, updated_at=NOW()
+            WHERE transaction_id=$1 RETURNING *;";
+        let row = client
+            .query_opt(stmt, &[transaction_id, cancels_transaction_id])
+            .await?;
+        Ok(row.map(|row| CkbTransaction::from_row_ref(&row).unwrap()))
+    }
+
+    pub async fn expire_transaction(
+        &self,
+        transaction_id: &String,
+        reason: &String,
+        statuses: &[i16],
+    ) -> Result<bool, PoolError> {
+        let client: Client = self.db.get().await?;
+        let stmt = "UPDATE transactions SET status=$1, status_reason=$2, updated_at=NOW() WHERE transaction_id=$3 AND status = ANY($4)";
+        let res = client
+            .execute(
+                stmt,
+                &[
+                    &TRANSACTION_STATUS_EXPIRED,
+                    reason,
+                    transaction_id,
+                    &statuses,
+                ],
+            )
+            .await?;
+        Ok(res > 0)
+    }
+
+    pub async fn expire_overdue_transactions(
+        &self,
+        reason: &String,
+    ) -> Result<Vec<String>, PoolError> {
+        let client: Client = self.db.get().await?;
+        let _stmt = "UPDATE transactions SET status=$1, status_reason=$2, updated_at=NOW()
+            WHERE status = ANY($3) AND expires_at < NOW()
+            RETURNING transaction_id;";
+        let stmt = client.prepare(_stmt).await?;
+
+        let txids = client
+            .query(
+                &stmt,
+                &[
+                    &TRANSACTION_STATUS_EXPIRED,
+                    reason,
+                    &UNSENT_TRANSACTION_STATUSES.as_slice(),
+                ],
+            )
+            .await?
+            .iter()
+            .map(|row| row.get(0))
+            .collect::<Vec<String>>();
+
+        Ok(txids)
+    }
+
     pub async fn update_transaction_status(
         &self,
         transaction_id: &String,
@@ -567,15 +1112,16 @@ impl MultiSigDao {
         signer_address: &String,
         transaction_id: &String,
         errors: &String,
+        category: &Option<String>,
     ) -> Result<bool, PoolError> {
         let client: Client = self.db.get().await?;
 
         // Create tx
         let stmt =
-            "INSERT INTO transaction_errors (transaction_id, signer_address, error_msg) VALUES ($1, $2, $3);";
+            "INSERT INTO transaction_errors (transaction_id, signer_address, error_msg, category) VALUES ($1, $2, $3, $4);";
         let stmt = client.prepare(stmt).await?;
         let res = client
-            .execute(&stmt, &[transaction_id, signer_address, errors])
+            .execute(&stmt, &[transaction_id, signer_address, errors, category])
             .await
             .map_err(|err| AppError::new(500).message(&err.to_string()))
             .unwrap();
@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use crate::models::multi_sig_message::{
+    MultiSigMessage, MultiSigMessageSignature, MESSAGE_STATUS_COMPLETED, MESSAGE_STATUS_PENDING,
+};
+use deadpool_postgres::{Client, Pool, PoolError};
+use tokio_pg_mapper::FromTokioPostgresRow;
+
+#[derive(Clone, Debug)]
+pub struct MultiSigMessageDao {
+    db: Arc<Pool>,
+}
+
+impl MultiSigMessageDao {
+    pub fn new(db: Arc<Pool>) -> Self {
+        MultiSigMessageDao { db: db.clone() }
+    }
+
+    pub async fn create_message(
+        &self,
+        multi_sig_address: &String,
+        proposer_address: &String,
+        message: &String,
+        digest: &String,
+        signature: &String,
+    ) -> Result<MultiSigMessage, PoolError> {
+        let mut client: Client = self.db.get().await?;
+        let db_transaction = client.transaction().await?;
+
+        let stmt =
+            "INSERT INTO multi_sig_messages (multi_sig_address, proposer_address, message, digest)
+            VALUES ($1, $2, $3, $4) RETURNING *;";
+        let row = db_transaction
+            .query_one(
+                stmt,
+                &[multi_sig_address, proposer_address, message, digest],
+            )
+            .await?;
+        let multi_sig_message = MultiSigMessage::from_row(row).unwrap();
+
+        let stmt =
+            "INSERT INTO multi_sig_message_signatures (message_id, signer_address, signature)
+            VALUES ($1, $2, $3);";
+        db_transaction
+            .execute(stmt, &[&multi_sig_message.id, proposer_address, signature])
+            .await?;
+
+        db_transaction.commit().await?;
+        Ok(multi_sig_message)
+    }
+
+    pub async fn get_message(&self, id: i32) -> Result<Option<MultiSigMessage>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "SELECT * FROM multi_sig_messages WHERE id=$1;";
+        let row = client.query_opt(stmt, &[&id]).await?;
+        Ok(row.map(|row| MultiSigMessage::from_row_ref(&row).unwrap()))
+    }
+
+    pub async fn get_messages_by_address(
+        &self,
+        multi_sig_address: &String,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<MultiSigMessage>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let _stmt = "SELECT * FROM multi_sig_messages WHERE multi_sig_address=$1
+            ORDER BY created_at DESC, id DESC OFFSET $2 LIMIT $3;";
+        let stmt = client.prepare(_stmt).await?;
+
+        let messages = client
+            .query(&stmt, &[multi_sig_address, &offset, &limit])
+            .await?
+            .iter()
+            .map(|row| MultiSigMessage::from_row_ref(row).unwrap())
+            .collect::<Vec<MultiSigMessage>>();
+
+        Ok(messages)
+    }
+
+    pub async fn count_messages_by_address(
+        &self,
+        multi_sig_address: &String,
+    ) -> Result<i64, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "SELECT COUNT(*) FROM multi_sig_messages WHERE multi_sig_address=$1;";
+        let row = client.query_one(stmt, &[multi_sig_address]).await?;
+        Ok(row.get(0))
+    }
+
+    pub async fn get_signatures(
+        &self,
+        message_id: i32,
+    ) -> Result<Vec<MultiSigMessageSignature>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let _stmt = "SELECT * FROM multi_sig_message_signatures WHERE message_id=$1
+            ORDER BY created_at ASC;";
+        let stmt = client.prepare(_stmt).await?;
+
+        let signatures = client
+            .query(&stmt, &[&message_id])
+            .await?
+            .iter()
+            .map(|row| MultiSigMessageSignature::from_row_ref(row).unwrap())
+            .collect::<Vec<MultiSigMessageSignature>>();
+
+        Ok(signatures)
+    }
+
+    /// Returns false when the signer already signed or the message is no longer pending.
+    pub async fn add_signature(
+        &self,
+        message_id: i32,
+        signer_address: &String,
+        signature: &String,
+    ) -> Result<bool, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt =
+            "INSERT INTO multi_sig_message_signatures (message_id, signer_address, signature)
+            SELECT id, $2, $3 FROM multi_sig_messages WHERE id=$1 AND status=$4
+            ON CONFLICT (message_id, signer_address) DO NOTHING;";
+        let inserted = client
+            .execute(
+                stmt,
+                &[
+                    &message_id,
+                    signer_address,
+                    signature,
+                    &MESSAGE_STATUS_PENDING,
+                ],
+            )
+            .await?;
+        Ok(inserted > 0)
+    }
+
+    pub async fn complete_message(
+        &self,
+        message_id: i32,
+        proof: &String,
+    ) -> Result<Option<MultiSigMessage>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "UPDATE multi_sig_messages SET status=$1, proof=$2, updated_at=NOW()
+            WHERE id=$3 AND status=$4 RETURNING *;";
+        let row = client
+            .query_opt(
+                stmt,
+                &[
+                    &MESSAGE_STATUS_COMPLETED,
+                    proof,
+                    &message_id,
+                    &MESSAGE_STATUS_PENDING,
+                ],
+            )
+            .await?;
+        Ok(row.map(|row| MultiSigMessage::from_row_ref(&row).unwrap()))
+    }
+}
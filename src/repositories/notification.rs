@@ -0,0 +1,140 @@
+use std::sync::Arc;
+
+use deadpool_postgres::{Client, Pool, PoolError};
+use tokio_pg_mapper::FromTokioPostgresRow;
+
+use crate::models::notification::Notification;
+use crate::serialize::event::AccountEvent;
+
+#[derive(Clone, Debug)]
+pub struct NotificationDao {
+    db: Arc<Pool>,
+}
+
+impl NotificationDao {
+    pub fn new(db: Arc<Pool>) -> Self {
+        NotificationDao { db: db.clone() }
+    }
+
+    pub async fn create_notification(
+        &self,
+        user_address: &String,
+        event: &AccountEvent,
+        title: &String,
+        body: &String,
+        link: &String,
+    ) -> Result<(), PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "INSERT INTO notifications
+                (user_address, event, multi_sig_address, transaction_id, actor_address,
+                title, body, link)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8);";
+        client
+            .execute(
+                stmt,
+                &[
+                    user_address,
+                    &event.event,
+                    &event.multi_sig_address,
+                    &event.transaction_id,
+                    &event.actor_address,
+                    title,
+                    body,
+                    link,
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// One notification for every signer of the account but the actor.
+    pub async fn create_account_notifications(
+        &self,
+        event: &AccountEvent,
+        title: &String,
+        body: &String,
+        link: &String,
+    ) -> Result<u64, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "INSERT INTO notifications
+                (user_address, event, multi_sig_address, transaction_id, actor_address,
+                title, body, link)
+            SELECT signer_address, $2, $1, $3, $4, $5, $6, $7 FROM multi_sig_signers
+            WHERE multi_sig_address=$1 AND signer_address IS DISTINCT FROM $4;";
+        let inserted = client
+            .execute(
+                stmt,
+                &[
+                    &event.multi_sig_address,
+                    &event.event,
+                    &event.transaction_id,
+                    &event.actor_address,
+                    title,
+                    body,
+                    link,
+                ],
+            )
+            .await?;
+        Ok(inserted)
+    }
+
+    pub async fn get_notifications(
+        &self,
+        user_address: &String,
+        unread_only: bool,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<Notification>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let _stmt = "SELECT * FROM notifications
+            WHERE user_address=$1 AND ($2 = FALSE OR read_at IS NULL)
+            ORDER BY created_at DESC, id DESC OFFSET $3 LIMIT $4;";
+        let stmt = client.prepare(_stmt).await?;
+
+        let notifications = client
+            .query(&stmt, &[user_address, &unread_only, &offset, &limit])
+            .await?
+            .iter()
+            .map(|row| Notification::from_row_ref(row).unwrap())
+            .collect::<Vec<Notification>>();
+
+        Ok(notifications)
+    }
+
+    pub async fn count_notifications(
+        &self,
+        user_address: &String,
+        unread_only: bool,
+    ) -> Result<i64, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "SELECT COUNT(*) FROM notifications
+            WHERE user_address=$1 AND ($2 = FALSE OR read_at IS NULL);";
+        let row = client
+            .query_one(stmt, &[user_address, &unread_only])
+            .await?;
+        Ok(row.get(0))
+    }
+
+    /// Mark the given notifications of the user as read, ids of other users are ignored.
+    pub async fn mark_read(&self, user_address: &String, ids: &[i32]) -> Result<u64, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "UPDATE notifications SET read_at=NOW()
+            WHERE user_address=$1 AND id = ANY($2) AND read_at IS NULL;";
+        let updated = client.execute(stmt, &[user_address, &ids]).await?;
+        Ok(updated)
+    }
+
+    pub async fn mark_all_read(&self, user_address: &String) -> Result<u64, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "UPDATE notifications SET read_at=NOW()
+            WHERE user_address=$1 AND read_at IS NULL;";
+        let updated = client.execute(stmt, &[user_address]).await?;
+        Ok(updated)
+    }
+}
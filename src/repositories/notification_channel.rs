@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use crate::models::notification_channel::NotificationChannel;
+use deadpool_postgres::{Client, Pool, PoolError};
+use tokio_pg_mapper::FromTokioPostgresRow;
+
+#[derive(Clone, Debug)]
+pub struct NotificationChannelDao {
+    db: Arc<Pool>,
+}
+
+impl NotificationChannelDao {
+    pub fn new(db: Arc<Pool>) -> Self {
+        NotificationChannelDao { db: db.clone() }
+    }
+
+    pub async fn create_channel(
+        &self,
+        multi_sig_address: &String,
+        kind: &String,
+        target: &String,
+        events: &Vec<String>,
+        created_by: &String,
+    ) -> Result<NotificationChannel, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "INSERT INTO notification_channels
+                (multi_sig_address, kind, target, events, created_by)
+            VALUES ($1, $2, $3, $4, $5) RETURNING *;";
+        let row = client
+            .query_one(stmt, &[multi_sig_address, kind, target, events, created_by])
+            .await?;
+        Ok(NotificationChannel::from_row(row).unwrap())
+    }
+
+    pub async fn get_channel(&self, id: i32) -> Result<Option<NotificationChannel>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "SELECT * FROM notification_channels WHERE id=$1;";
+        let row = client.query_opt(stmt, &[&id]).await?;
+        Ok(row.map(|row| NotificationChannel::from_row_ref(&row).unwrap()))
+    }
+
+    pub async fn get_channels_by_address(
+        &self,
+        multi_sig_address: &String,
+    ) -> Result<Vec<NotificationChannel>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let _stmt =
+            "SELECT * FROM notification_channels WHERE multi_sig_address=$1 ORDER BY id ASC;";
+        let stmt = client.prepare(_stmt).await?;
+
+        let channels = client
+            .query(&stmt, &[multi_sig_address])
+            .await?
+            .iter()
+            .map(|row| NotificationChannel::from_row_ref(row).unwrap())
+            .collect::<Vec<NotificationChannel>>();
+
+        Ok(channels)
+    }
+
+    /// Active channels of the account listening to the event.
+    pub async fn get_channels_for_event(
+        &self,
+        multi_sig_address: &String,
+        event: &String,
+    ) -> Result<Vec<NotificationChannel>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let _stmt = "SELECT * FROM notification_channels
+            WHERE multi_sig_address=$1 AND active=TRUE AND $2=ANY(events);";
+        let stmt = client.prepare(_stmt).await?;
+
+        let channels = client
+            .query(&stmt, &[multi_sig_address, event])
+            .await?
+            .iter()
+            .map(|row| NotificationChannel::from_row_ref(row).unwrap())
+            .collect::<Vec<NotificationChannel>>();
+
+        Ok(channels)
+    }
+
+    pub async fn update_channel(
+        &self,
+        id: i32,
+        target: &String,
+        events: &Vec<String>,
+        active: bool,
+    ) -> Result<NotificationChannel, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "UPDATE notification_channels
+            SET target=$1, events=$2, active=$3, updated_at=NOW()
+            WHERE id=$4 RETURNING *;";
+        let row = client
+            .query_one(stmt, &[target, events, &active, &id])
+            .await?;
+        Ok(NotificationChannel::from_row(row).unwrap())
+    }
+
+    pub async fn delete_channel(&self, id: i32) -> Result<bool, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "DELETE FROM notification_channels WHERE id=$1;";
+        let deleted = client.execute(stmt, &[&id]).await?;
+        Ok(deleted > 0)
+    }
+}
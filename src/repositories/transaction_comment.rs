@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use crate::models::transaction_comment::TransactionComment;
+use deadpool_postgres::{Client, Pool, PoolError};
+use tokio_pg_mapper::FromTokioPostgresRow;
+
+#[derive(Clone, Debug)]
+pub struct TransactionCommentDao {
+    db: Arc<Pool>,
+}
+
+impl TransactionCommentDao {
+    pub fn new(db: Arc<Pool>) -> Self {
+        TransactionCommentDao { db: db.clone() }
+    }
+
+    pub async fn get_comments_by_txid(
+        &self,
+        transaction_id: &String,
+    ) -> Result<Vec<TransactionComment>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let _stmt =
+            "SELECT * FROM transaction_comments WHERE transaction_id=$1 ORDER BY created_at ASC, id ASC;";
+        let stmt = client.prepare(_stmt).await?;
+
+        let comments = client
+            .query(&stmt, &[transaction_id])
+            .await?
+            .iter()
+            .map(|row| TransactionComment::from_row_ref(row).unwrap())
+            .collect::<Vec<TransactionComment>>();
+
+        Ok(comments)
+    }
+
+    pub async fn count_comments_by_txid(&self, transaction_id: &String) -> Result<i64, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "SELECT COUNT(*) FROM transaction_comments WHERE transaction_id=$1;";
+        let row = client.query_one(stmt, &[transaction_id]).await?;
+        Ok(row.get(0))
+    }
+
+    pub async fn get_comment(
+        &self,
+        transaction_id: &String,
+        id: i32,
+    ) -> Result<Option<TransactionComment>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "SELECT * FROM transaction_comments WHERE transaction_id=$1 AND id=$2;";
+        let row = client.query_opt(stmt, &[transaction_id, &id]).await?;
+        Ok(row.map(|row| TransactionComment::from_row_ref(&row).unwrap()))
+    }
+
+    pub async fn add_comment(
+        &self,
+        transaction_id: &String,
+        author_address: &String,
+        body: &String,
+    ) -> Result<TransactionComment, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "INSERT INTO transaction_comments (transaction_id, author_address, body)
+            VALUES ($1, $2, $3) RETURNING *;";
+        let row = client
+            .query_one(stmt, &[transaction_id, author_address, body])
+            .await?;
+        Ok(TransactionComment::from_row(row).unwrap())
+    }
+
+    pub async fn update_comment(
+        &self,
+        id: i32,
+        author_address: &String,
+        body: &String,
+    ) -> Result<Option<TransactionComment>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "UPDATE transaction_comments
+            SET body=$1, edited_at=NOW(), updated_at=NOW()
+            WHERE id=$2 AND author_address=$3 RETURNING *;";
+        let row = client.query_opt(stmt, &[body, &id, author_address]).await?;
+        Ok(row.map(|row| TransactionComment::from_row_ref(&row).unwrap()))
+    }
+}
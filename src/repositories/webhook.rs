@@ -0,0 +1,245 @@
+use std::sync::Arc;
+
+use crate::models::webhook::{
+    Webhook, WebhookDelivery, DELIVERY_STATUS_DELIVERED, DELIVERY_STATUS_FAILED,
+    DELIVERY_STATUS_PENDING,
+};
+use deadpool_postgres::{Client, Pool, PoolError};
+use tokio_pg_mapper::FromTokioPostgresRow;
+
+#[derive(Clone, Debug)]
+pub struct WebhookDao {
+    db: Arc<Pool>,
+}
+
+impl WebhookDao {
+    pub fn new(db: Arc<Pool>) -> Self {
+        WebhookDao { db: db.clone() }
+    }
+
+    pub async fn create_webhook(
+        &self,
+        multi_sig_address: &String,
+        url: &String,
+        secret: &String,
+        events: &Vec<String>,
+        created_by: &String,
+    ) -> Result<Webhook, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "INSERT INTO webhooks (multi_sig_address, url, secret, events, created_by)
+            VALUES ($1, $2, $3, $4, $5) RETURNING *;";
+        let row = client
+            .query_one(stmt, &[multi_sig_address, url, secret, events, created_by])
+            .await?;
+        Ok(Webhook::from_row(row).unwrap())
+    }
+
+    pub async fn get_webhook(&self, id: i32) -> Result<Option<Webhook>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "SELECT * FROM webhooks WHERE id=$1;";
+        let row = client.query_opt(stmt, &[&id]).await?;
+        Ok(row.map(|row| Webhook::from_row_ref(&row).unwrap()))
+    }
+
+    pub async fn get_webhooks_by_address(
+        &self,
+        multi_sig_address: &String,
+    ) -> Result<Vec<Webhook>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let _stmt = "SELECT * FROM webhooks WHERE multi_sig_address=$1 ORDER BY id ASC;";
+        let stmt = client.prepare(_stmt).await?;
+
+        let webhooks = client
+            .query(&stmt, &[multi_sig_address])
+            .await?
+            .iter()
+            .map(|row| Webhook::from_row_ref(row).unwrap())
+            .collect::<Vec<Webhook>>();
+
+        Ok(webhooks)
+    }
+
+    pub async fn update_webhook(
+        &self,
+        id: i32,
+        url: &String,
+        events: &Vec<String>,
+        active: bool,
+    ) -> Result<Webhook, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "UPDATE webhooks SET url=$1, events=$2, active=$3, updated_at=NOW()
+            WHERE id=$4 RETURNING *;";
+        let row = client.query_one(stmt, &[url, events, &active, &id]).await?;
+        Ok(Webhook::from_row(row).unwrap())
+    }
+
+    pub async fn delete_webhook(&self, id: i32) -> Result<bool, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "DELETE FROM webhooks WHERE id=$1;";
+        let deleted = client.execute(stmt, &[&id]).await?;
+        Ok(deleted > 0)
+    }
+
+    /// Queue the event for every active subscription of the account listening to it.
+    pub async fn enqueue_deliveries(
+        &self,
+        multi_sig_address: &String,
+        event: &String,
+        payload: &String,
+    ) -> Result<u64, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "INSERT INTO webhook_deliveries (webhook_id, event, payload)
+            SELECT id, $2, $3 FROM webhooks
+            WHERE multi_sig_address=$1 AND active=TRUE AND $2=ANY(events);";
+        let queued = client
+            .execute(stmt, &[multi_sig_address, event, payload])
+            .await?;
+        Ok(queued)
+    }
+
+    /// Queue a copy of a past delivery, the original stays in the log untouched.
+    pub async fn redeliver(&self, delivery_id: i32) -> Result<WebhookDelivery, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "INSERT INTO webhook_deliveries (webhook_id, event, payload)
+            SELECT webhook_id, event, payload FROM webhook_deliveries WHERE id=$1
+            RETURNING *;";
+        let row = client.query_one(stmt, &[&delivery_id]).await?;
+        Ok(WebhookDelivery::from_row(row).unwrap())
+    }
+
+    pub async fn get_delivery(&self, id: i32) -> Result<Option<WebhookDelivery>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "SELECT * FROM webhook_deliveries WHERE id=$1;";
+        let row = client.query_opt(stmt, &[&id]).await?;
+        Ok(row.map(|row| WebhookDelivery::from_row_ref(&row).unwrap()))
+    }
+
+    pub async fn get_deliveries(
+        &self,
+        webhook_id: i32,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let _stmt = "SELECT * FROM webhook_deliveries WHERE webhook_id=$1
+            ORDER BY created_at DESC, id DESC OFFSET $2 LIMIT $3;";
+        let stmt = client.prepare(_stmt).await?;
+
+        let deliveries = client
+            .query(&stmt, &[&webhook_id, &offset, &limit])
+            .await?
+            .iter()
+            .map(|row| WebhookDelivery::from_row_ref(row).unwrap())
+            .collect::<Vec<WebhookDelivery>>();
+
+        Ok(deliveries)
+    }
+
+    pub async fn count_deliveries(&self, webhook_id: i32) -> Result<i64, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "SELECT COUNT(*) FROM webhook_deliveries WHERE webhook_id=$1;";
+        let row = client.query_one(stmt, &[&webhook_id]).await?;
+        Ok(row.get(0))
+    }
+
+    /// Take the due deliveries off the queue. Claimed rows are pushed `lease_secs` into the
+    /// future, so other replicas leave them alone while they are being sent.
+    pub async fn claim_due_deliveries(
+        &self,
+        limit: i64,
+        lease_secs: i32,
+    ) -> Result<Vec<WebhookDelivery>, PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let _stmt = "UPDATE webhook_deliveries
+            SET next_attempt_at=NOW() + ($2::INTEGER * INTERVAL '1 second'), updated_at=NOW()
+            WHERE id IN (
+                SELECT id FROM webhook_deliveries
+                WHERE status=$1 AND next_attempt_at<=NOW()
+                ORDER BY next_attempt_at ASC LIMIT $3
+                FOR UPDATE SKIP LOCKED
+            ) RETURNING *;";
+        let stmt = client.prepare(_stmt).await?;
+
+        let deliveries = client
+            .query(&stmt, &[&DELIVERY_STATUS_PENDING, &lease_secs, &limit])
+            .await?
+            .iter()
+            .map(|row| WebhookDelivery::from_row_ref(row).unwrap())
+            .collect::<Vec<WebhookDelivery>>();
+
+        Ok(deliveries)
+    }
+
+    pub async fn mark_delivered(
+        &self,
+        id: i32,
+        response_status: i32,
+        response_body: &String,
+    ) -> Result<(), PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let stmt = "UPDATE webhook_deliveries
+            SET status=$1, attempts=attempts + 1, response_status=$2, response_body=$3,
+                last_error=NULL, delivered_at=NOW(), updated_at=NOW()
+            WHERE id=$4;";
+        client
+            .execute(
+                stmt,
+                &[
+                    &DELIVERY_STATUS_DELIVERED,
+                    &response_status,
+                    response_body,
+                    &id,
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Record a failed attempt. Without `retry_in_secs` the delivery gives up.
+    pub async fn mark_attempt_failed(
+        &self,
+        id: i32,
+        response_status: Option<i32>,
+        response_body: Option<String>,
+        error: &String,
+        retry_in_secs: Option<i32>,
+    ) -> Result<(), PoolError> {
+        let client: Client = self.db.get().await?;
+
+        let status = match retry_in_secs {
+            Some(_) => DELIVERY_STATUS_PENDING,
+            None => DELIVERY_STATUS_FAILED,
+        };
+        let stmt = "UPDATE webhook_deliveries
+            SET status=$1, attempts=attempts + 1, response_status=$2, response_body=$3,
+                last_error=$4, updated_at=NOW(),
+                next_attempt_at=NOW() + (COALESCE($5::INTEGER, 0) * INTERVAL '1 second')
+            WHERE id=$6;";
+        client
+            .execute(
+                stmt,
+                &[
+                    &status,
+                    &response_status,
+                    &response_body,
+                    error,
+                    &retry_in_secs,
+                    &id,
+                ],
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,22 @@
+use std::collections::HashMap;
+
+use ckb_jsonrpc_types::{JsonBytes, Transaction};
+use ckb_types::H160;
+use serde::{Deserialize, Serialize};
+
+// Mirrors ckb-cli's `tx` file format so it can be signed with `ckb-cli tx sign-inputs`
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CkbCliTx {
+    pub transaction: Transaction,
+    // keyed by the multisig lock args
+    pub multisig_configs: HashMap<H160, CkbCliMultisigConfig>,
+    // keyed by the lock args of the signed inputs
+    pub signatures: HashMap<JsonBytes, Vec<JsonBytes>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CkbCliMultisigConfig {
+    pub sighash_addresses: Vec<String>,
+    pub require_first_n: u8,
+    pub threshold: u8,
+}
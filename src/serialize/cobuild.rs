@@ -0,0 +1,17 @@
+use ckb_jsonrpc_types::JsonBytes;
+use serde::{Deserialize, Serialize};
+
+// A molecule encoded CoBuild `BuildingPacket`
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BuildingPacketReq {
+    pub building_packet: JsonBytes,
+    // Only used when the packet opens a new proposal
+    pub expires_at: Option<i64>,
+    pub memo: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BuildingPacketRes {
+    pub transaction_id: String,
+    pub building_packet: JsonBytes,
+}
@@ -0,0 +1,24 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RegisterEmailReq {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct VerifyEmailReq {
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct EmailPreferencesReq {
+    // event name => whether to get an email, events left out are unchanged
+    pub events: BTreeMap<String, bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct EmailPreferencesRes {
+    pub events: BTreeMap<String, bool>,
+}
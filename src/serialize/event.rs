@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+// An account event, as sent to webhooks and event streams
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AccountEvent {
+    pub id: String,
+    pub event: String,
+    pub multi_sig_address: String,
+    pub transaction_id: Option<String>,
+    // the signer behind the event, none for chain driven events
+    pub actor_address: Option<String>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct EventStreamFilters {
+    // only stream the events of this account
+    pub multi_sig_address: Option<String>,
+}
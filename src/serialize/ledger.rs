@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct LedgerContextFilters {
+    // BIP32 paths, e.g. m/44'/309'/0'/0/0
+    pub sign_path: Option<String>,
+    pub change_path: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LedgerSigningContext {
+    pub transaction_id: String,
+    pub multi_sig_address: String,
+    pub sign_path: String,
+    pub change_path: String,
+    // the multisig lock zeroed in the first witness
+    pub transaction: ckb_jsonrpc_types::Transaction,
+    // previous transaction of every input, in input order
+    pub input_transactions: Vec<ckb_jsonrpc_types::Transaction>,
+    pub signing_message: String,
+    // `S | R | M | N | blake160(pubkey) * N`, the prefix of the witness lock
+    pub multisig_config: String,
+    // molecule `AnnotatedTransaction`, ready for the app's sign command
+    pub annotated_transaction: String,
+}
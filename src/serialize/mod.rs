@@ -17,7 +17,19 @@ pub struct PaginationRes {
 }
 
 pub mod address_book;
+pub mod ckb_cli;
+pub mod cobuild;
+pub mod email;
 pub mod error;
+pub mod event;
+pub mod ledger;
 pub mod multi_sig_account;
+pub mod multi_sig_message;
+pub mod neuron;
+pub mod notification;
+pub mod notification_channel;
 pub mod transaction;
+pub mod transaction_comment;
+pub mod ur;
 pub mod user;
+pub mod webhook;
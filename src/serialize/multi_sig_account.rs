@@ -2,7 +2,10 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::models::{multi_sig_account::MultiSigSigner, multi_sig_invite::MultiSigInvite};
+use crate::models::{
+    multi_sig_account::MultiSigSigner, multi_sig_invite::MultiSigInvite,
+    multi_sig_tx::CkbTransaction,
+};
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct SignerInfo {
@@ -21,12 +24,40 @@ pub struct NewMultiSigAccountReq {
 pub struct MultiSigAccountUpdateReq {
     pub multi_sig_address: String,
     pub name: String,
+    pub fee_rate: Option<i64>,
+    // 0 removes the default validity window
+    pub proposal_validity_hours: Option<i32>,
+    // false waits for an explicit execute once the threshold is reached
+    pub auto_broadcast: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct NewTransferReq {
     pub signature: String,
     pub payload: String,
+    pub expires_at: Option<i64>,
+    pub memo: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct NewTransferRes {
+    #[serde(flatten)]
+    pub transaction: CkbTransaction,
+    pub conflicts: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct OnChainCancelRes {
+    pub transaction_id: String,
+    pub cancel_tx_hash: String,
+    pub payload: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct OnChainCancelReq {
+    // the cancellation built by `cancel-onchain`, signed by the caller
+    pub payload: String,
+    pub signature: String,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -41,6 +72,23 @@ pub struct TransactionFilters {
     pub limit: Option<i64>,
     pub status: Option<String>,
     pub tx_hash: Option<String>,
+    pub label: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct TransactionExportFilters {
+    // csv (default) or json
+    pub format: Option<String>,
+    // unix timestamps, `to` is exclusive
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TransactionLabelReq {
+    pub labels: Vec<String>,
+    // empty string clears the category
+    pub category: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -65,6 +113,15 @@ pub struct ListSignerRes {
     pub invites: Vec<MultiSigInvite>,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct FeeRateEstimateRes {
+    pub min_fee_rate: u64,
+    pub max_fee_rate: u64,
+    pub mean_fee_rate: u64,
+    pub median_fee_rate: u64,
+    pub default_fee_rate: u64,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct UpdateTransactionStatusReq {
     pub tx_hashes: Vec<String>,
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::multi_sig_message::MultiSigMessage;
+
+use super::PaginationRes;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct NewMessageReq {
+    pub message: String,
+    // the proposer's signature of the message digest
+    pub signature: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct MessageSignatureReq {
+    pub signature: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct MessageFilters {
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct MessageInfo {
+    #[serde(flatten)]
+    pub message: MultiSigMessage,
+    pub threshold: i16,
+    pub signers: Vec<String>,
+    // molecule `WitnessArgs` carrying the proof as its lock
+    pub witness: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ListMessagesRes {
+    pub messages: Vec<MessageInfo>,
+    pub pagination: PaginationRes,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct VerifyMessageReq {
+    pub multi_sig_address: String,
+    pub message: String,
+    // multisig lock: `S | R | M | N | blake160(pubkey) * N | signature * M`
+    pub proof: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct VerifyMessageRes {
+    pub valid: bool,
+    pub signers: Vec<String>,
+    pub reason: Option<String>,
+}
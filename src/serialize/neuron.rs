@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+// Neuron's offline signature file, the transaction uses Neuron's camelCase model
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct NeuronOfflineTx {
+    pub transaction: NeuronTransaction,
+    pub status: String,
+    #[serde(rename = "type")]
+    pub tx_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<Vec<serde_json::Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NeuronTransaction {
+    pub version: String,
+    pub cell_deps: Vec<NeuronCellDep>,
+    pub header_deps: Vec<String>,
+    pub inputs: Vec<NeuronInput>,
+    pub outputs: Vec<NeuronOutput>,
+    pub outputs_data: Vec<String>,
+    pub witnesses: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    // multisig lock hash => blake160 of the signers who signed
+    #[serde(default)]
+    pub signatures: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NeuronOutPoint {
+    pub tx_hash: String,
+    pub index: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NeuronCellDep {
+    pub out_point: NeuronOutPoint,
+    pub dep_type: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NeuronScript {
+    pub code_hash: String,
+    pub args: String,
+    pub hash_type: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NeuronInput {
+    pub previous_output: NeuronOutPoint,
+    pub since: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock: Option<NeuronScript>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_hash: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NeuronOutput {
+    pub capacity: String,
+    pub lock: NeuronScript,
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub type_: Option<NeuronScript>,
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::notification::Notification;
+
+use super::PaginationRes;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct NotificationFilters {
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+    // only the unread notifications
+    pub unread: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ListNotificationsRes {
+    pub notifications: Vec<Notification>,
+    pub pagination: PaginationRes,
+    pub unread_count: i64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct MarkReadReq {
+    pub ids: Vec<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct MarkReadRes {
+    pub updated: u64,
+    pub unread_count: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct UnreadCountRes {
+    pub unread_count: i64,
+}
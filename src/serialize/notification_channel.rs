@@ -0,0 +1,17 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct NewChannelReq {
+    // telegram, discord or slack
+    pub kind: String,
+    pub target: String,
+    // empty subscribes to the proposal and execution events
+    pub events: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct UpdateChannelReq {
+    pub target: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub active: Option<bool>,
+}
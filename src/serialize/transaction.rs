@@ -4,17 +4,76 @@ use crate::models::multi_sig_tx::TransactionError;
 
 use super::PaginationRes;
 
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetType {
+    Ckb,
+    Sudt,
+    Xudt,
+    Unknown,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CellAsset {
+    pub asset_type: AssetType,
+    pub type_hash: Option<String>,
+    pub amount: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DecodedInput {
+    pub tx_hash: String,
+    pub index: u32,
+    pub address: Option<String>,
+    pub capacity: Option<u64>,
+    pub asset: Option<CellAsset>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DecodedOutput {
+    pub index: u32,
+    pub address: String,
+    pub capacity: u64,
+    pub is_change: bool,
+    pub asset: CellAsset,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DecodedTransaction {
+    pub inputs: Vec<DecodedInput>,
+    pub outputs: Vec<DecodedOutput>,
+    pub total_input: Option<u64>,
+    pub total_output: u64,
+    pub total_sent: u64,
+    pub fee: Option<u64>,
+    pub assets: Vec<AssetType>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PreflightVerdict {
+    pub status: String,
+    pub message: Option<String>,
+    pub checked_at: i64,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct TransactionInfo {
     pub transaction_id: String,
     pub multi_sig_address: String,
-    pub to_address: String,
     pub confirmed: Vec<String>,
     pub rejected: Vec<String>,
     pub status: i16,
     pub payload: String,
     pub created_at: i64,
-    pub amount: u64,
+    pub expires_at: Option<i64>,
+    pub status_reason: Option<String>,
+    pub memo: Option<String>,
+    pub labels: Vec<String>,
+    pub category: Option<String>,
+    pub preflight: Option<PreflightVerdict>,
+    pub decoded: DecodedTransaction,
+    pub conflicts: Vec<String>,
+    pub comment_count: i64,
     pub errors: Option<Vec<TransactionError>>,
 }
 
@@ -29,3 +88,55 @@ pub struct ListTransactionsRes {
     pub transactions: Vec<TransactionInfo>,
     pub pagination: PaginationRes,
 }
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ImportSignaturesRes {
+    pub transaction_id: String,
+    pub imported: Vec<String>,
+    pub status: i16,
+}
+
+// Everything needed to finish a proposal without this server
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TransactionBundle {
+    pub transaction_id: String,
+    pub multi_sig_address: String,
+    pub status: i16,
+    // collected signatures already placed in the first witness
+    pub transaction: ckb_jsonrpc_types::Transaction,
+    pub signing_message: String,
+    pub multisig_config: BundleMultisigConfig,
+    pub signers: Vec<String>,
+    pub missing_signers: Vec<String>,
+    pub missing_signatures: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BundleMultisigConfig {
+    // `S | R | M | N | blake160(pubkey) * N`, the prefix of the witness lock
+    pub witness_data: String,
+    pub sighash_addresses: Vec<String>,
+    pub require_first_n: u8,
+    pub threshold: u8,
+}
+
+// One row of the accounting export, a proposal or an incoming transfer
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TransactionExportRow {
+    pub direction: String,
+    pub transaction_id: String,
+    pub status: String,
+    pub created_at: i64,
+    pub broadcast_at: Option<i64>,
+    pub block_number: Option<i64>,
+    // shannons sent by the account, or received for incoming transfers
+    pub amount: u64,
+    // `type_hash:amount` of the sUDT / xUDT moved
+    pub tokens: Vec<String>,
+    pub fee: Option<u64>,
+    pub counterparties: Vec<String>,
+    pub signers: Vec<String>,
+    pub memo: Option<String>,
+    pub category: Option<String>,
+    pub labels: Vec<String>,
+}
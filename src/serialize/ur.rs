@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct UrFragmentFilters {
+    // bytes of message per part, smaller parts make denser QR codes easier to scan
+    pub fragment_size: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct UrSignRequestRes {
+    pub transaction_id: String,
+    pub ur_type: String,
+    pub parts: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct UrSignatureReq {
+    pub parts: Vec<String>,
+}
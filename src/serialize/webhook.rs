@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::webhook::{Webhook, WebhookDelivery};
+
+use super::PaginationRes;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct NewWebhookReq {
+    pub url: String,
+    // empty subscribes to every event
+    pub events: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct UpdateWebhookReq {
+    pub url: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct NewWebhookRes {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    // key of the `X-Webhook-Signature` HMAC-SHA256, not shown again
+    pub secret: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct DeliveryFilters {
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ListDeliveriesRes {
+    pub deliveries: Vec<WebhookDelivery>,
+    pub pagination: PaginationRes,
+}
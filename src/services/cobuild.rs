@@ -0,0 +1,245 @@
+// CKB CoBuild witness layouts and building packets. The molecule types are not part of
+// ckb-types yet, they are encoded by hand following
+// https://github.com/cryptape/ckb-cobuild-docs/blob/main/mol/basic.mol
+//
+// The accounts use the system secp256k1 multisig lock, which reads its signatures from the
+// `WitnessArgs` of the first witness. CoBuild is therefore only the `BuildingPacket` transport
+// of a proposal, witness layouts are parsed to be rejected where the lock is sealed.
+use ckb_types::bytes::Bytes;
+use ckb_types::packed::{BytesVec, CellOutput, CellOutputVec, Transaction};
+use ckb_types::prelude::{Builder, Entity, Pack};
+
+use crate::serialize::error::AppError;
+
+pub const WITNESS_LAYOUT_SIGHASH_ALL: u32 = 0xFF000001;
+pub const WITNESS_LAYOUT_SIGHASH_ALL_ONLY: u32 = 0xFF000002;
+const BUILDING_PACKET_V1: u32 = 0;
+
+// An empty ActionVec / ScriptInfoVec
+const EMPTY_DYNVEC: [u8; 4] = [4, 0, 0, 0];
+
+fn invalid(message: &str) -> AppError {
+    AppError::new(400).message(message)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, AppError> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or(invalid("invalid molecule data"))
+}
+
+pub fn pack_table(fields: &[&[u8]]) -> Vec<u8> {
+    let header_size = 4 * (fields.len() + 1);
+    let total_size = header_size + fields.iter().map(|field| field.len()).sum::<usize>();
+
+    let mut data = Vec::with_capacity(total_size);
+    data.extend_from_slice(&(total_size as u32).to_le_bytes());
+    let mut offset = header_size;
+    for field in fields {
+        data.extend_from_slice(&(offset as u32).to_le_bytes());
+        offset += field.len();
+    }
+    for field in fields {
+        data.extend_from_slice(field);
+    }
+    data
+}
+
+/// Split a molecule table into its fields. Extra fields appended by newer schemas are kept.
+fn unpack_table(data: &[u8], min_fields: usize) -> Result<Vec<&[u8]>, AppError> {
+    let total_size = read_u32(data, 0)? as usize;
+    if total_size != data.len() {
+        return Err(invalid("invalid molecule table size"));
+    }
+    if total_size == 4 {
+        return if min_fields == 0 {
+            Ok(vec![])
+        } else {
+            Err(invalid("molecule table has too few fields"))
+        };
+    }
+
+    let first_offset = read_u32(data, 4)? as usize;
+    if !first_offset.is_multiple_of(4) || first_offset < 8 {
+        return Err(invalid("invalid molecule table header"));
+    }
+    let field_count = first_offset / 4 - 1;
+    if field_count < min_fields {
+        return Err(invalid("molecule table has too few fields"));
+    }
+
+    let mut offsets = vec![];
+    for index in 0..field_count {
+        offsets.push(read_u32(data, 4 * (index + 1))? as usize);
+    }
+    offsets.push(total_size);
+
+    let mut fields = vec![];
+    for index in 0..field_count {
+        let (start, end) = (offsets[index], offsets[index + 1]);
+        if start > end || end > total_size {
+            return Err(invalid("invalid molecule table offsets"));
+        }
+        fields.push(&data[start..end]);
+    }
+    Ok(fields)
+}
+
+fn unpack_bytes(data: &[u8]) -> Result<Bytes, AppError> {
+    let len = read_u32(data, 0)? as usize;
+    if data.len() != len + 4 {
+        return Err(invalid("invalid molecule bytes"));
+    }
+    Ok(Bytes::copy_from_slice(&data[4..]))
+}
+
+/// A `Message` without any action.
+pub fn empty_message() -> Bytes {
+    Bytes::from(pack_table(&[&EMPTY_DYNVEC]))
+}
+
+fn is_empty_message(message: &[u8]) -> bool {
+    unpack_table(message, 1)
+        .map(|fields| fields[0] == EMPTY_DYNVEC)
+        .unwrap_or(false)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WitnessLayout {
+    // `message` keeps the raw molecule `Message`
+    SighashAll { message: Bytes, seal: Bytes },
+    SighashAllOnly { seal: Bytes },
+}
+
+impl WitnessLayout {
+    /// Parse a witness as a CoBuild layout. Returns `None` for legacy `WitnessArgs`
+    /// witnesses, whose first 4 bytes are a table size and never reach the layout ids.
+    pub fn from_witness(witness: &[u8]) -> Option<Result<WitnessLayout, AppError>> {
+        let id = read_u32(witness, 0).ok()?;
+        let item = &witness[4..];
+        match id {
+            WITNESS_LAYOUT_SIGHASH_ALL => Some(unpack_table(item, 2).and_then(|fields| {
+                unpack_table(fields[0], 1)?;
+                Ok(WitnessLayout::SighashAll {
+                    message: Bytes::copy_from_slice(fields[0]),
+                    seal: unpack_bytes(fields[1])?,
+                })
+            })),
+            WITNESS_LAYOUT_SIGHASH_ALL_ONLY => Some(unpack_table(item, 1).and_then(|fields| {
+                Ok(WitnessLayout::SighashAllOnly {
+                    seal: unpack_bytes(fields[0])?,
+                })
+            })),
+            id if id >= 0xFF000000 => Some(Err(invalid("unsupported CoBuild witness layout"))),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BuildingPacket {
+    pub message: Bytes,
+    pub payload: Transaction,
+    pub resolved_inputs: Vec<(CellOutput, Bytes)>,
+    pub change_output: Option<u32>,
+    // Kept as raw molecule vectors, the API doesn't interpret them
+    pub script_infos: Bytes,
+    pub lock_actions: Bytes,
+}
+
+impl BuildingPacket {
+    pub fn new(
+        message: Bytes,
+        payload: Transaction,
+        resolved_inputs: Vec<(CellOutput, Bytes)>,
+        change_output: Option<u32>,
+    ) -> Self {
+        BuildingPacket {
+            message,
+            payload,
+            resolved_inputs,
+            change_output,
+            script_infos: Bytes::from(EMPTY_DYNVEC.to_vec()),
+            lock_actions: Bytes::from(EMPTY_DYNVEC.to_vec()),
+        }
+    }
+
+    pub fn has_actions(&self) -> bool {
+        !is_empty_message(&self.message)
+    }
+
+    pub fn as_bytes(&self) -> Bytes {
+        let outputs = CellOutputVec::new_builder()
+            .extend(
+                self.resolved_inputs
+                    .iter()
+                    .map(|(output, _)| output.clone()),
+            )
+            .build();
+        let outputs_data = BytesVec::new_builder()
+            .extend(self.resolved_inputs.iter().map(|(_, data)| data.pack()))
+            .build();
+        let resolved_inputs = pack_table(&[outputs.as_slice(), outputs_data.as_slice()]);
+        let change_output = self
+            .change_output
+            .map(|index| index.to_le_bytes().to_vec())
+            .unwrap_or_default();
+
+        let item = pack_table(&[
+            &self.message,
+            self.payload.as_slice(),
+            &resolved_inputs,
+            &change_output,
+            &self.script_infos,
+            &self.lock_actions,
+        ]);
+
+        let mut data = BUILDING_PACKET_V1.to_le_bytes().to_vec();
+        data.extend_from_slice(&item);
+        Bytes::from(data)
+    }
+
+    pub fn from_slice(data: &[u8]) -> Result<BuildingPacket, AppError> {
+        if read_u32(data, 0)? != BUILDING_PACKET_V1 {
+            return Err(invalid("unsupported building packet version"));
+        }
+
+        let fields = unpack_table(&data[4..], 6)?;
+        unpack_table(fields[0], 1)?;
+        let payload = Transaction::from_slice(fields[1])
+            .map_err(|err| AppError::new(400).cause(err).message("invalid payload"))?;
+
+        let resolved_inputs = unpack_table(fields[2], 2)?;
+        let outputs = CellOutputVec::from_slice(resolved_inputs[0]).map_err(|err| {
+            AppError::new(400)
+                .cause(err)
+                .message("invalid resolved inputs")
+        })?;
+        let outputs_data = BytesVec::from_slice(resolved_inputs[1]).map_err(|err| {
+            AppError::new(400)
+                .cause(err)
+                .message("invalid resolved inputs")
+        })?;
+        if outputs.len() != outputs_data.len() {
+            return Err(invalid("invalid resolved inputs"));
+        }
+
+        let change_output = match fields[3].len() {
+            0 => None,
+            4 => Some(read_u32(fields[3], 0)?),
+            _ => return Err(invalid("invalid change output")),
+        };
+
+        Ok(BuildingPacket {
+            message: Bytes::copy_from_slice(fields[0]),
+            payload,
+            resolved_inputs: outputs
+                .into_iter()
+                .zip(outputs_data.into_iter().map(|data| data.raw_data()))
+                .collect(),
+            change_output,
+            script_infos: Bytes::copy_from_slice(fields[4]),
+            lock_actions: Bytes::copy_from_slice(fields[5]),
+        })
+    }
+}
@@ -5,3 +5,18 @@ pub const TESTNET_MULTISIG_CODE_HASH: H256 =
 
 pub const MAINNET_MULTISIG_CODE_HASH: H256 =
     h256!("0xd1a9f877aed3f5e07cb9c52b61ab96d06f250ae6883cc7f0a2423db0976fc821");
+
+pub const TESTNET_SUDT_CODE_HASH: H256 =
+    h256!("0xc5e5dcf215925f7ef4dfaf5f4b4f105bc321c02776d6e7d52a1db3fcd9d011a4");
+
+pub const MAINNET_SUDT_CODE_HASH: H256 =
+    h256!("0x5e7a36a77e68eecc013dfa2fe6a23f3b6c344b04005808694ae6dd45eea4cfd5");
+
+pub const TESTNET_XUDT_CODE_HASH: H256 =
+    h256!("0x25c29dc317811a6f6f3985a7a9ebc4838bd388d19d0feeecf0bcd60f6c0975bb");
+
+pub const MAINNET_XUDT_CODE_HASH: H256 =
+    h256!("0x50bd8d6680b8b9cf98b73f3c08faf8b2a21914311954118ad6609be6e78a1b95");
+
+// shannons per KB, same as the node's default `min_fee_rate`
+pub const DEFAULT_FEE_RATE: i64 = 1000;
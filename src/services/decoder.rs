@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use ckb_sdk::{Address, AddressPayload};
+use ckb_types::bytes::Bytes;
+use ckb_types::core::TransactionView;
+use ckb_types::packed::{CellOutput, Script};
+use ckb_types::prelude::{Entity, Unpack};
+use ckb_types::H256;
+use once_cell::sync::Lazy;
+
+use crate::repositories::ckb::{
+    get_ckb_network, get_sudt_script_hash, get_transaction_body, get_xudt_script_hash,
+};
+use crate::serialize::transaction::{
+    AssetType, CellAsset, DecodedInput, DecodedOutput, DecodedTransaction,
+};
+
+pub fn script_to_address(lock: &Script) -> String {
+    Address::new(
+        get_ckb_network(),
+        AddressPayload::new_full(
+            lock.hash_type().try_into().unwrap(),
+            lock.code_hash(),
+            lock.args().unpack(),
+        ),
+        true,
+    )
+    .to_string()
+}
+
+pub fn detect_asset(output: &CellOutput, data: &[u8]) -> CellAsset {
+    match output.type_().to_opt() {
+        None => CellAsset {
+            asset_type: AssetType::Ckb,
+            type_hash: None,
+            amount: None,
+        },
+        Some(type_script) => {
+            let code_hash: H256 = type_script.code_hash().unpack();
+            let asset_type = if code_hash == get_sudt_script_hash() {
+                AssetType::Sudt
+            } else if code_hash == get_xudt_script_hash() {
+                AssetType::Xudt
+            } else {
+                AssetType::Unknown
+            };
+
+            // Both sUDT and xUDT keep the token amount in the first 16 bytes of cell data
+            let amount = match asset_type {
+                AssetType::Sudt | AssetType::Xudt if data.len() >= 16 => {
+                    let mut amount = [0u8; 16];
+                    amount.copy_from_slice(&data[0..16]);
+                    Some(u128::from_le_bytes(amount).to_string())
+                }
+                _ => None,
+            };
+
+            CellAsset {
+                asset_type,
+                type_hash: Some(hex::encode(type_script.calc_script_hash().raw_data())),
+                amount,
+            }
+        }
+    }
+}
+
+// A transaction never changes once it is known by its hash, so the previous transactions
+// are kept across requests. The cache is simply emptied when it fills up.
+const PREVIOUS_TX_CACHE_SIZE: usize = 4096;
+static PREVIOUS_TX_CACHE: Lazy<Mutex<HashMap<H256, ckb_jsonrpc_types::Transaction>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+async fn get_previous_transaction(tx_hash: &H256) -> Option<ckb_jsonrpc_types::Transaction> {
+    let cached = PREVIOUS_TX_CACHE.lock().unwrap().get(tx_hash).cloned();
+    if cached.is_some() {
+        return cached;
+    }
+
+    let previous_tx = get_transaction_body(tx_hash.clone()).await.ok().flatten();
+    if let Some(previous_tx) = &previous_tx {
+        let mut cache = PREVIOUS_TX_CACHE.lock().unwrap();
+        if cache.len() >= PREVIOUS_TX_CACHE_SIZE {
+            cache.clear();
+        }
+        cache.insert(tx_hash.clone(), previous_tx.clone());
+    }
+    previous_tx
+}
+
+/// Load the previous output of every input from the node. The result keeps
+/// the input order, inputs that can not be resolved are `None`.
+pub async fn resolve_inputs(tx: &TransactionView) -> Vec<Option<(CellOutput, Bytes)>> {
+    let mut previous_txs: HashMap<H256, Option<ckb_jsonrpc_types::Transaction>> = HashMap::new();
+    let mut resolved = vec![];
+
+    for out_point in tx.input_pts_iter() {
+        let tx_hash: H256 = out_point.tx_hash().unpack();
+        let index: u32 = out_point.index().unpack();
+
+        if !previous_txs.contains_key(&tx_hash) {
+            let previous_tx = get_previous_transaction(&tx_hash).await;
+            previous_txs.insert(tx_hash.clone(), previous_tx);
+        }
+
+        let cell = previous_txs
+            .get(&tx_hash)
+            .cloned()
+            .flatten()
+            .and_then(|previous_tx| {
+                let output = previous_tx.outputs.get(index as usize)?.clone();
+                let data = previous_tx
+                    .outputs_data
+                    .get(index as usize)
+                    .map(|data| data.clone().into_bytes())
+                    .unwrap_or_default();
+                Some((CellOutput::from(output), data))
+            });
+        resolved.push(cell);
+    }
+
+    resolved
+}
+
+pub fn decode_outputs(multi_sig_address: &str, tx: &TransactionView) -> Vec<DecodedOutput> {
+    let multi_sig_lock = Address::from_str(multi_sig_address)
+        .ok()
+        .map(|address| Script::from(&address));
+
+    tx.outputs_with_data_iter()
+        .enumerate()
+        .map(|(index, (output, data))| {
+            let lock = output.lock();
+            let is_change = multi_sig_lock
+                .as_ref()
+                .map(|multi_sig_lock| multi_sig_lock.as_slice() == lock.as_slice())
+                .unwrap_or(false);
+
+            DecodedOutput {
+                index: index as u32,
+                address: script_to_address(&lock),
+                capacity: output.capacity().unpack(),
+                is_change,
+                asset: detect_asset(&output, &data),
+            }
+        })
+        .collect()
+}
+
+pub async fn decode_transaction(
+    multi_sig_address: &str,
+    tx: &TransactionView,
+) -> DecodedTransaction {
+    let resolved = resolve_inputs(tx).await;
+
+    let inputs: Vec<DecodedInput> = tx
+        .input_pts_iter()
+        .zip(resolved.iter())
+        .map(|(out_point, cell)| DecodedInput {
+            tx_hash: hex::encode(out_point.tx_hash().raw_data()),
+            index: out_point.index().unpack(),
+            address: cell
+                .as_ref()
+                .map(|(output, _)| script_to_address(&output.lock())),
+            capacity: cell.as_ref().map(|(output, _)| output.capacity().unpack()),
+            asset: cell
+                .as_ref()
+                .map(|(output, data)| detect_asset(output, data)),
+        })
+        .collect();
+    let outputs = decode_outputs(multi_sig_address, tx);
+
+    let total_input: Option<u64> = inputs.iter().map(|input| input.capacity).sum();
+    let total_output: u64 = outputs.iter().map(|output| output.capacity).sum();
+    let total_sent: u64 = outputs
+        .iter()
+        .filter(|output| !output.is_change)
+        .map(|output| output.capacity)
+        .sum();
+    let fee = total_input.and_then(|total_input| total_input.checked_sub(total_output));
+
+    let mut assets: Vec<AssetType> = vec![];
+    let cell_assets = inputs
+        .iter()
+        .filter_map(|input| input.asset.as_ref())
+        .chain(outputs.iter().map(|output| &output.asset));
+    for asset in cell_assets {
+        if !assets.contains(&asset.asset_type) {
+            assets.push(asset.asset_type.clone());
+        }
+    }
+
+    DecodedTransaction {
+        inputs,
+        outputs,
+        total_input,
+        total_output,
+        total_sent,
+        fee,
+        assets,
+    }
+}
@@ -0,0 +1,349 @@
+use std::collections::BTreeMap;
+
+use chrono::{Duration, Utc};
+use lettre::message::{header::ContentType, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use rand::Rng;
+
+use crate::config;
+use crate::models::email::{OutboxEmail, UserEmail};
+use crate::repositories::email::EmailDao;
+use crate::serialize::email::{
+    EmailPreferencesReq, EmailPreferencesRes, RegisterEmailReq, VerifyEmailReq,
+};
+use crate::serialize::error::AppError;
+use crate::serialize::event::AccountEvent;
+use crate::services::events::{
+    event_summary, ACCOUNT_EVENTS, EVENT_INVITE_CREATED, EVENT_PROPOSAL_CREATED,
+};
+
+const LAYOUT_HTML: &str = include_str!("../../templates/email/layout.html");
+const LAYOUT_TEXT: &str = include_str!("../../templates/email/layout.txt");
+
+// What signers get until they change their preferences: invites and proposals to sign
+pub const EMAIL_DEFAULT_EVENTS: [&str; 2] = [EVENT_INVITE_CREATED, EVENT_PROPOSAL_CREATED];
+
+const MAX_SEND_RETRY_DELAY_SECS: i32 = 6 * 3600;
+const SEND_BATCH_SIZE: i64 = 20;
+const SEND_LEASE_SECS: i32 = 120;
+
+pub struct EmailContent {
+    pub subject: String,
+    pub heading: String,
+    pub body: String,
+    pub action_label: String,
+    pub action_url: String,
+}
+
+impl EmailContent {
+    /// Fill the layouts, returns the HTML and the plain text bodies.
+    pub fn render(&self) -> (String, String) {
+        let fill = |layout: &str, escape: fn(&str) -> String| {
+            layout
+                .replace("{{subject}}", &escape(&self.subject))
+                .replace("{{heading}}", &escape(&self.heading))
+                .replace("{{body}}", &escape(&self.body))
+                .replace("{{action_label}}", &escape(&self.action_label))
+                .replace("{{action_url}}", &escape(&self.action_url))
+        };
+        (
+            fill(LAYOUT_HTML, escape_html),
+            fill(LAYOUT_TEXT, str::to_owned),
+        )
+    }
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+pub fn email_enabled_by_default(event: &str) -> bool {
+    EMAIL_DEFAULT_EVENTS.contains(&event)
+}
+
+pub fn event_email(event: &AccountEvent) -> EmailContent {
+    let summary = event_summary(event);
+    EmailContent {
+        subject: summary.title.clone(),
+        heading: summary.title,
+        body: summary.body,
+        action_label: summary.action_label,
+        action_url: summary.link,
+    }
+}
+
+/// Queue the emails of an event. Invites go to the invited signer, everything else to the
+/// other signers of the account.
+pub async fn queue_event_emails(
+    email_dao: &EmailDao,
+    event: &AccountEvent,
+) -> Result<(), AppError> {
+    let enabled_by_default = email_enabled_by_default(&event.event);
+    let recipients = if event.event == EVENT_INVITE_CREATED {
+        match &event.actor_address {
+            Some(invitee) => email_dao
+                .get_user_recipient(invitee, &event.event, enabled_by_default)
+                .await
+                .map_err(|err| AppError::new(500).message(&err.to_string()))?
+                .into_iter()
+                .collect(),
+            None => vec![],
+        }
+    } else {
+        email_dao
+            .get_account_recipients(
+                &event.multi_sig_address,
+                &event.event,
+                &event.actor_address,
+                enabled_by_default,
+            )
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+    };
+    if recipients.is_empty() {
+        return Ok(());
+    }
+
+    let content = event_email(event);
+    let (html_body, text_body) = content.render();
+    for recipient in recipients {
+        email_dao
+            .enqueue_email(&recipient.email, &content.subject, &html_body, &text_body)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug)]
+pub struct EmailSrv {
+    email_dao: EmailDao,
+    mailer: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl EmailSrv {
+    pub fn new(email_dao: EmailDao) -> Self {
+        EmailSrv {
+            email_dao: email_dao.clone(),
+            mailer: build_mailer(),
+        }
+    }
+
+    pub async fn get_email(&self, user_address: &str) -> Result<UserEmail, AppError> {
+        self.email_dao
+            .get_email(&user_address.to_owned())
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Email not registered"))
+    }
+
+    /// Register or change the email of the user and send it a verification link.
+    pub async fn register_email(
+        &self,
+        user_address: &str,
+        req: RegisterEmailReq,
+    ) -> Result<UserEmail, AppError> {
+        let email = req.email.trim().to_lowercase();
+        email
+            .parse::<lettre::Address>()
+            .map_err(|err| AppError::new(400).cause(err).message("Invalid email"))?;
+
+        let ttl_hours: i64 = config::get("email_verification_ttl_hours");
+        let token = hex::encode(rand::thread_rng().gen::<[u8; 32]>());
+        let user_email = self
+            .email_dao
+            .upsert_email(
+                &user_address.to_owned(),
+                &email,
+                &token,
+                &(Utc::now().naive_utc() + Duration::hours(ttl_hours)),
+            )
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+
+        let app_url: String = config::get("app_url");
+        let content = EmailContent {
+            subject: "Verify your email".to_owned(),
+            heading: "Verify your email".to_owned(),
+            body: format!(
+                "Confirm this address to get notifications of your multisig accounts. The link expires in {} hours.",
+                ttl_hours
+            ),
+            action_label: "Verify email".to_owned(),
+            action_url: format!(
+                "{}/verify-email?token={}",
+                app_url.trim_end_matches('/'),
+                token
+            ),
+        };
+        let (html_body, text_body) = content.render();
+        self.email_dao
+            .enqueue_email(&email, &content.subject, &html_body, &text_body)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+
+        Ok(user_email)
+    }
+
+    pub async fn verify_email(
+        &self,
+        user_address: &str,
+        req: VerifyEmailReq,
+    ) -> Result<UserEmail, AppError> {
+        self.email_dao
+            .verify_email(&user_address.to_owned(), &req.token)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(400).message("Invalid or expired verification token"))
+    }
+
+    pub async fn delete_email(&self, user_address: &str) -> Result<bool, AppError> {
+        self.email_dao
+            .delete_email(&user_address.to_owned())
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))
+    }
+
+    pub async fn get_preferences(
+        &self,
+        user_address: &str,
+    ) -> Result<EmailPreferencesRes, AppError> {
+        let preferences = self
+            .email_dao
+            .get_preferences(&user_address.to_owned())
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+
+        let mut events: BTreeMap<String, bool> = ACCOUNT_EVENTS
+            .iter()
+            .map(|event| (event.to_string(), email_enabled_by_default(event)))
+            .collect();
+        for preference in preferences {
+            if let Some(enabled) = events.get_mut(&preference.event) {
+                *enabled = preference.email;
+            }
+        }
+        Ok(EmailPreferencesRes { events })
+    }
+
+    pub async fn update_preferences(
+        &self,
+        user_address: &str,
+        req: EmailPreferencesReq,
+    ) -> Result<EmailPreferencesRes, AppError> {
+        if let Some(event) = req
+            .events
+            .keys()
+            .find(|event| !ACCOUNT_EVENTS.contains(&event.as_str()))
+        {
+            return Err(AppError::new(400).message(&format!("Unknown event {}", event)));
+        }
+
+        for (event, enabled) in &req.events {
+            self.email_dao
+                .set_preference(&user_address.to_owned(), event, *enabled)
+                .await
+                .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        }
+        self.get_preferences(user_address).await
+    }
+
+    /// Send the emails of the outbox which are due, retrying failures with backoff.
+    pub async fn send_due(&self) -> Result<(), AppError> {
+        let emails = self
+            .email_dao
+            .claim_due_emails(SEND_BATCH_SIZE, SEND_LEASE_SECS)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+
+        for email in emails {
+            let result = match self.send(&email).await {
+                Ok(_) => self.email_dao.mark_sent(email.id).await,
+                Err(err) => {
+                    log::warn!("send email {} failed: {}", email.id, err);
+                    self.email_dao
+                        .mark_attempt_failed(email.id, &err, retry_delay(email.attempts + 1))
+                        .await
+                }
+            };
+            if let Err(err) = result {
+                log::error!("update outbox email {} failed: {}", email.id, err);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn send(&self, email: &OutboxEmail) -> Result<(), String> {
+        let from: String = config::get("smtp_from");
+        let from = from.parse::<Mailbox>().map_err(|err| err.to_string())?;
+        let to = email
+            .recipient
+            .parse::<Mailbox>()
+            .map_err(|err| err.to_string())?;
+
+        let message = Message::builder()
+            .from(from)
+            .to(to)
+            .subject(&email.subject)
+            .multipart(
+                MultiPart::alternative()
+                    .singlepart(
+                        SinglePart::builder()
+                            .header(ContentType::TEXT_PLAIN)
+                            .body(email.text_body.clone()),
+                    )
+                    .singlepart(
+                        SinglePart::builder()
+                            .header(ContentType::TEXT_HTML)
+                            .body(email.html_body.clone()),
+                    ),
+            )
+            .map_err(|err| err.to_string())?;
+
+        self.mailer
+            .send(message)
+            .await
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+fn retry_delay(attempts: i32) -> Option<i32> {
+    let max_attempts: i32 = config::get("email_max_attempts");
+    let backoff_secs: i32 = config::get("email_retry_backoff_secs");
+
+    (attempts < max_attempts).then(|| {
+        backoff_secs
+            .saturating_mul(2i32.saturating_pow(attempts as u32 - 1))
+            .min(MAX_SEND_RETRY_DELAY_SECS)
+    })
+}
+
+/// `smtp_tls` is `tls`, `starttls` or `none`, the latter for a local SMTP sink.
+fn build_mailer() -> AsyncSmtpTransport<Tokio1Executor> {
+    let host: String = config::get("smtp_host");
+    let port: u16 = config::get("smtp_port");
+    let tls: String = config::get("smtp_tls");
+    let username: String = config::get("smtp_username");
+    let password: String = config::get("smtp_password");
+
+    let builder = match tls.as_str() {
+        "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(&host).unwrap(),
+        "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host).unwrap(),
+        _ => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&host),
+    };
+    let builder = builder.port(port);
+    let builder = if username.is_empty() {
+        builder
+    } else {
+        builder.credentials(Credentials::new(username, password))
+    };
+    builder.build()
+}
@@ -0,0 +1,439 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use chrono::Utc;
+use deadpool_postgres::tokio_postgres::{self, AsyncMessage, NoTls};
+use futures_util::{stream, Stream, StreamExt};
+use tokio::sync::{broadcast, mpsc};
+use uuid::Uuid;
+
+use crate::config;
+use crate::repositories::email::EmailDao;
+use crate::repositories::event::EventDao;
+use crate::repositories::multi_sig_account::MultiSigDao;
+use crate::repositories::notification::NotificationDao;
+use crate::repositories::notification_channel::NotificationChannelDao;
+use crate::repositories::webhook::WebhookDao;
+use crate::serialize::error::AppError;
+use crate::serialize::event::{AccountEvent, EventStreamFilters};
+use crate::services::email::queue_event_emails;
+use crate::services::notification::record_event_notifications;
+use crate::services::notification_channel::notifier_client;
+use crate::services::notifier::send_notification;
+use ckb_types::bytes::Bytes;
+
+// Postgres channel the events go through, every replica listens to it
+pub const EVENT_CHANNEL: &str = "account_events";
+// Events buffered per stream before a slow client starts missing some
+pub const EVENT_HUB_CAPACITY: usize = 1024;
+
+pub const EVENT_PROPOSAL_CREATED: &str = "proposal.created";
+pub const EVENT_SIGNATURE_ADDED: &str = "signature.added";
+pub const EVENT_THRESHOLD_REACHED: &str = "threshold.reached";
+pub const EVENT_TRANSACTION_BROADCAST: &str = "transaction.broadcast";
+pub const EVENT_TRANSACTION_COMMITTED: &str = "transaction.committed";
+pub const EVENT_TRANSACTION_FAILED: &str = "transaction.failed";
+pub const EVENT_TRANSACTION_REJECTED: &str = "transaction.rejected";
+pub const EVENT_INVITE_CREATED: &str = "invite.created";
+pub const EVENT_INVITE_ACCEPTED: &str = "invite.accepted";
+pub const EVENT_INVITE_REJECTED: &str = "invite.rejected";
+
+pub const ACCOUNT_EVENTS: [&str; 10] = [
+    EVENT_PROPOSAL_CREATED,
+    EVENT_SIGNATURE_ADDED,
+    EVENT_THRESHOLD_REACHED,
+    EVENT_TRANSACTION_BROADCAST,
+    EVENT_TRANSACTION_COMMITTED,
+    EVENT_TRANSACTION_FAILED,
+    EVENT_TRANSACTION_REJECTED,
+    EVENT_INVITE_CREATED,
+    EVENT_INVITE_ACCEPTED,
+    EVENT_INVITE_REJECTED,
+];
+
+/// Where the app shows what the event is about.
+pub fn event_link(event: &AccountEvent) -> String {
+    let app_url: String = config::get("app_url");
+    let app_url = app_url.trim_end_matches('/');
+
+    if event.event.starts_with("invite.") {
+        return format!("{}/invites", app_url);
+    }
+    match &event.transaction_id {
+        Some(txid) => format!(
+            "{}/accounts/{}/transactions/{}",
+            app_url, event.multi_sig_address, txid
+        ),
+        None => format!("{}/accounts/{}", app_url, event.multi_sig_address),
+    }
+}
+
+pub struct EventSummary {
+    pub title: String,
+    pub body: String,
+    pub action_label: String,
+    pub link: String,
+}
+
+fn short_address(address: &str) -> String {
+    if address.len() <= 20 {
+        return address.to_owned();
+    }
+    format!("{}...{}", &address[..10], &address[address.len() - 6..])
+}
+
+/// Human readable title and text of an event.
+pub fn event_summary(event: &AccountEvent) -> EventSummary {
+    let account = short_address(&event.multi_sig_address);
+    let actor = event
+        .actor_address
+        .as_deref()
+        .map(short_address)
+        .unwrap_or("A signer".to_owned());
+    let txid = event
+        .transaction_id
+        .as_deref()
+        .map(short_address)
+        .unwrap_or_default();
+
+    let (title, body, action_label) = match event.event.as_str() {
+        EVENT_INVITE_CREATED => (
+            format!("You are invited to the multisig account {}", account),
+            format!(
+                "You were added as a signer of the multisig account {}. Accept the invite to see and sign its proposals.",
+                account
+            ),
+            "Review invite",
+        ),
+        EVENT_INVITE_ACCEPTED => (
+            format!("{} joined {}", actor, account),
+            format!("{} accepted the invite to the multisig account {}.", actor, account),
+            "Open account",
+        ),
+        EVENT_INVITE_REJECTED => (
+            format!("{} declined to join {}", actor, account),
+            format!("{} rejected the invite to the multisig account {}.", actor, account),
+            "Open account",
+        ),
+        EVENT_PROPOSAL_CREATED => (
+            format!("New proposal waiting for your signature on {}", account),
+            format!(
+                "{} proposed the transaction {} on the multisig account {}. It needs your signature.",
+                actor, txid, account
+            ),
+            "Review and sign",
+        ),
+        EVENT_SIGNATURE_ADDED => (
+            format!("New signature on {}", account),
+            format!("{} signed the transaction {} of {}.", actor, txid, account),
+            "View transaction",
+        ),
+        EVENT_THRESHOLD_REACHED => (
+            format!("Proposal fully signed on {}", account),
+            format!("The transaction {} of {} collected enough signatures.", txid, account),
+            "View transaction",
+        ),
+        EVENT_TRANSACTION_BROADCAST => (
+            format!("Transaction sent from {}", account),
+            format!("The transaction {} of {} was sent to the network.", txid, account),
+            "View transaction",
+        ),
+        EVENT_TRANSACTION_COMMITTED => (
+            format!("Transaction confirmed on {}", account),
+            format!("The transaction {} of {} is confirmed on chain.", txid, account),
+            "View transaction",
+        ),
+        EVENT_TRANSACTION_FAILED => (
+            format!("Transaction failed on {}", account),
+            format!("The transaction {} of {} failed. It may be retried.", txid, account),
+            "View transaction",
+        ),
+        EVENT_TRANSACTION_REJECTED => (
+            format!("Proposal rejected on {}", account),
+            format!("The transaction {} of {} was rejected by its signers.", txid, account),
+            "View transaction",
+        ),
+        other => (
+            format!("{} on {}", other, account),
+            format!("{} on the multisig account {}.", other, account),
+            "Open account",
+        ),
+    };
+
+    EventSummary {
+        title,
+        body,
+        action_label: action_label.to_owned(),
+        link: event_link(event),
+    }
+}
+
+/// Fans account events out to their subscribers. Publishing never fails the action which
+/// raised the event, errors are only logged.
+#[derive(Clone, Debug)]
+pub struct EventDispatcher {
+    webhook_dao: WebhookDao,
+    event_dao: EventDao,
+    email_dao: EmailDao,
+    notification_channel_dao: NotificationChannelDao,
+    notification_dao: NotificationDao,
+    client: reqwest::Client,
+}
+
+impl EventDispatcher {
+    pub fn new(
+        webhook_dao: WebhookDao,
+        event_dao: EventDao,
+        email_dao: EmailDao,
+        notification_channel_dao: NotificationChannelDao,
+        notification_dao: NotificationDao,
+    ) -> Self {
+        EventDispatcher {
+            webhook_dao: webhook_dao.clone(),
+            event_dao: event_dao.clone(),
+            email_dao: email_dao.clone(),
+            notification_channel_dao: notification_channel_dao.clone(),
+            notification_dao: notification_dao.clone(),
+            client: notifier_client(),
+        }
+    }
+
+    pub async fn publish(
+        &self,
+        event: &str,
+        multi_sig_address: &str,
+        transaction_id: Option<&str>,
+        actor_address: Option<&str>,
+    ) {
+        let account_event = AccountEvent {
+            id: Uuid::new_v4().to_string(),
+            event: event.to_owned(),
+            multi_sig_address: multi_sig_address.to_owned(),
+            transaction_id: transaction_id.map(|txid| txid.to_owned()),
+            actor_address: actor_address.map(|address| address.to_owned()),
+            created_at: Utc::now().timestamp(),
+        };
+        let payload = serde_json::to_string(&account_event).unwrap();
+
+        if let Err(err) = self
+            .webhook_dao
+            .enqueue_deliveries(
+                &account_event.multi_sig_address,
+                &account_event.event,
+                &payload,
+            )
+            .await
+        {
+            log::error!("queue webhook deliveries of {} failed: {}", event, err);
+        }
+        if let Err(err) = queue_event_emails(&self.email_dao, &account_event).await {
+            log::error!("queue emails of {} failed: {}", event, err);
+        }
+        if let Err(err) = record_event_notifications(&self.notification_dao, &account_event).await {
+            log::error!("record notifications of {} failed: {}", event, err);
+        }
+        if let Err(err) = self.event_dao.notify(EVENT_CHANNEL, &payload).await {
+            log::error!("notify {} failed: {}", event, err);
+        }
+        self.notify_channels(&account_event).await;
+    }
+
+    // Chat posts are best effort, they are sent off the request and not retried
+    async fn notify_channels(&self, event: &AccountEvent) {
+        let channels = match self
+            .notification_channel_dao
+            .get_channels_for_event(&event.multi_sig_address, &event.event)
+            .await
+        {
+            Ok(channels) => channels,
+            Err(err) => {
+                log::error!("get channels of {} failed: {}", event.event, err);
+                return;
+            }
+        };
+        if channels.is_empty() {
+            return;
+        }
+
+        let client = self.client.clone();
+        let summary = event_summary(event);
+        tokio::spawn(async move {
+            for channel in channels {
+                if let Err(err) = send_notification(&client, &channel, &summary).await {
+                    log::warn!("notify channel {} failed: {}", channel.id, err);
+                }
+            }
+        });
+    }
+}
+
+/// In-process fan-out of the events received from Postgres to the open streams.
+#[derive(Clone, Debug)]
+pub struct EventHub {
+    sender: broadcast::Sender<AccountEvent>,
+}
+
+impl EventHub {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        EventHub { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<AccountEvent> {
+        self.sender.subscribe()
+    }
+
+    /// LISTEN on the event channel with a dedicated connection until it breaks.
+    pub async fn listen(&self) -> Result<(), tokio_postgres::Error> {
+        let database_url: String = config::get("database_url");
+        let (client, mut connection) = tokio_postgres::connect(&database_url, NoTls).await?;
+
+        // The connection only makes progress while it is polled, drive it on its own task
+        let (payload_sender, mut payloads) = mpsc::unbounded_channel();
+        let driver = tokio::spawn(async move {
+            let mut messages = stream::poll_fn(move |cx| connection.poll_message(cx));
+            while let Some(message) = messages.next().await {
+                if let AsyncMessage::Notification(notification) = message? {
+                    if payload_sender
+                        .send(notification.payload().to_owned())
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            }
+            Ok(())
+        });
+
+        client
+            .batch_execute(&format!("LISTEN {};", EVENT_CHANNEL))
+            .await?;
+        while let Some(payload) = payloads.recv().await {
+            match serde_json::from_str::<AccountEvent>(&payload) {
+                // No receiver only means no stream is open
+                Ok(event) => {
+                    let _ = self.sender.send(event);
+                }
+                Err(err) => log::warn!("invalid event payload: {}", err),
+            }
+        }
+
+        driver.await.unwrap_or(Ok(()))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EventStreamSrv {
+    event_hub: EventHub,
+    multi_sig_dao: MultiSigDao,
+}
+
+impl EventStreamSrv {
+    pub fn new(event_hub: EventHub, multi_sig_dao: MultiSigDao) -> Self {
+        EventStreamSrv {
+            event_hub: event_hub.clone(),
+            multi_sig_dao: multi_sig_dao.clone(),
+        }
+    }
+
+    /// Server-Sent Events of the accounts the user signs for, and of the invites they get.
+    pub async fn stream_events(
+        &self,
+        user_address: &str,
+        filters: EventStreamFilters,
+    ) -> Result<impl Stream<Item = Result<Bytes, AppError>>, AppError> {
+        let accounts: HashSet<String> = self
+            .multi_sig_dao
+            .request_list_accounts(&user_address.to_owned())
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .into_iter()
+            .map(|account| account.multi_sig_address)
+            .collect();
+        if let Some(multi_sig_address) = &filters.multi_sig_address {
+            if !accounts.contains(multi_sig_address) {
+                return Err(AppError::new(404).message("not found"));
+            }
+        }
+
+        let keepalive_secs: u64 = config::get("event_stream_keepalive_secs");
+        let mut keepalive = tokio::time::interval(Duration::from_secs(keepalive_secs));
+        keepalive.reset();
+
+        let state = EventStreamState {
+            user_address: user_address.to_owned(),
+            accounts,
+            only_account: filters.multi_sig_address,
+            receiver: self.event_hub.subscribe(),
+            keepalive,
+            connected: false,
+        };
+
+        Ok(stream::unfold(state, |mut state| async move {
+            let chunk = state.next_chunk().await?;
+            Some((Ok(Bytes::from(chunk)), state))
+        }))
+    }
+}
+
+struct EventStreamState {
+    user_address: String,
+    accounts: HashSet<String>,
+    only_account: Option<String>,
+    receiver: broadcast::Receiver<AccountEvent>,
+    keepalive: tokio::time::Interval,
+    connected: bool,
+}
+
+impl EventStreamState {
+    async fn next_chunk(&mut self) -> Option<String> {
+        if !self.connected {
+            self.connected = true;
+            return Some(": connected\n\n".to_owned());
+        }
+
+        loop {
+            tokio::select! {
+                received = self.receiver.recv() => match received {
+                    Ok(event) => {
+                        if let Some(chunk) = self.visible_event(&event) {
+                            return Some(chunk);
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        log::warn!("event stream of {} skipped {} events", self.user_address, skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                },
+                // Comments keep proxies from closing an idle stream
+                _ = self.keepalive.tick() => return Some(": keepalive\n\n".to_owned()),
+            }
+        }
+    }
+
+    fn visible_event(&mut self, event: &AccountEvent) -> Option<String> {
+        let own_event = event.actor_address.as_deref() == Some(self.user_address.as_str());
+        if own_event && event.event == EVENT_INVITE_ACCEPTED {
+            self.accounts.insert(event.multi_sig_address.clone());
+        }
+
+        let own_invite = own_event && event.event.starts_with("invite.");
+        if !self.accounts.contains(&event.multi_sig_address) && !own_invite {
+            return None;
+        }
+        if self
+            .only_account
+            .as_ref()
+            .is_some_and(|account| account != &event.multi_sig_address)
+        {
+            return None;
+        }
+
+        Some(format!(
+            "id: {}\nevent: {}\ndata: {}\n\n",
+            event.id,
+            event.event,
+            serde_json::to_string(event).unwrap()
+        ))
+    }
+}
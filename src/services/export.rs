@@ -0,0 +1,141 @@
+use chrono::DateTime;
+
+use crate::serialize::error::AppError;
+use crate::serialize::transaction::TransactionExportRow;
+
+pub const EXPORT_DIRECTION_OUTGOING: &str = "outgoing";
+pub const EXPORT_DIRECTION_INCOMING: &str = "incoming";
+
+const CSV_HEADER: &str = "direction,transaction_id,status,created_at,broadcast_at,block_number,amount,tokens,fee,counterparties,signers,memo,category,labels\n";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn parse(format: Option<&str>) -> Result<Self, AppError> {
+        match format.map(|format| format.to_lowercase()).as_deref() {
+            None | Some("csv") => Ok(ExportFormat::Csv),
+            Some("json") => Ok(ExportFormat::Json),
+            Some(_) => Err(AppError::new(400).message("format must be csv or json")),
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Json => "application/json",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// Writes the export chunk by chunk: CSV rows under a header, or the elements of one JSON
+/// array.
+#[derive(Debug, Clone)]
+pub struct ExportWriter {
+    format: ExportFormat,
+    rows_written: usize,
+}
+
+impl ExportWriter {
+    pub fn new(format: ExportFormat) -> Self {
+        ExportWriter {
+            format,
+            rows_written: 0,
+        }
+    }
+
+    pub fn begin(&self) -> String {
+        match self.format {
+            ExportFormat::Csv => CSV_HEADER.to_owned(),
+            ExportFormat::Json => "[".to_owned(),
+        }
+    }
+
+    pub fn rows(&mut self, rows: &[TransactionExportRow]) -> String {
+        let mut chunk = String::new();
+        for row in rows {
+            match self.format {
+                ExportFormat::Csv => chunk.push_str(&csv_row(row)),
+                ExportFormat::Json => {
+                    if self.rows_written > 0 {
+                        chunk.push(',');
+                    }
+                    chunk.push_str(&serde_json::to_string(row).unwrap_or_default());
+                }
+            }
+            self.rows_written += 1;
+        }
+        chunk
+    }
+
+    pub fn end(&self) -> String {
+        match self.format {
+            ExportFormat::Csv => String::new(),
+            ExportFormat::Json => "]".to_owned(),
+        }
+    }
+}
+
+fn csv_row(row: &TransactionExportRow) -> String {
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    let fields = [
+        row.direction.clone(),
+        row.transaction_id.clone(),
+        row.status.clone(),
+        format_timestamp(row.created_at),
+        optional(row.broadcast_at.map(format_timestamp)),
+        optional(row.block_number.map(|number| number.to_string())),
+        row.amount.to_string(),
+        row.tokens.join(";"),
+        optional(row.fee.map(|fee| fee.to_string())),
+        row.counterparties.join(";"),
+        row.signers.join(";"),
+        text_field(optional(row.memo.clone())),
+        text_field(optional(row.category.clone())),
+        text_field(row.labels.join(";")),
+    ];
+
+    let mut line = fields
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<String>>()
+        .join(",");
+    line.push('\n');
+    line
+}
+
+// Spreadsheets evaluate cells starting with these as formulas
+const FORMULA_PREFIXES: [char; 4] = ['=', '+', '-', '@'];
+
+/// Keep user written text from being evaluated, a memo like `=HYPERLINK(...)` stays text.
+fn text_field(value: String) -> String {
+    if value.starts_with(FORMULA_PREFIXES) {
+        format!("'{}", value)
+    } else {
+        value
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+fn format_timestamp(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .map(|datetime| datetime.to_rfc3339())
+        .unwrap_or_default()
+}
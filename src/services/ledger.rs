@@ -0,0 +1,137 @@
+// Signing context of the Ledger CKB app. The app re-hashes every input against its
+// previous transaction before showing amounts, so it takes an `AnnotatedTransaction`
+// instead of a plain one:
+//
+// vector Bip32 <Uint32>;
+// table AnnotatedCellInput { input: CellInput, source: RawTransaction }
+// vector AnnotatedCellInputVec <AnnotatedCellInput>;
+// table AnnotatedRawTransaction {
+//     version: Uint32, cell_deps: CellDepVec, header_deps: Byte32Vec,
+//     inputs: AnnotatedCellInputVec, outputs: CellOutputVec, outputs_data: BytesVec,
+// }
+// table AnnotatedTransaction {
+//     signPath: Bip32, changePath: Bip32, inputCount: Uint32,
+//     raw: AnnotatedRawTransaction, witnesses: BytesVec,
+// }
+use std::collections::HashMap;
+
+use ckb_types::core::TransactionView;
+use ckb_types::packed::Transaction;
+use ckb_types::prelude::{Entity, Unpack};
+use ckb_types::H256;
+
+use crate::repositories::ckb::get_transaction_body;
+use crate::serialize::error::AppError;
+use crate::services::cobuild::pack_table;
+
+pub const DEFAULT_SIGN_PATH: &str = "m/44'/309'/0'/0/0";
+pub const DEFAULT_CHANGE_PATH: &str = "m/44'/309'/0'/1/0";
+
+const HARDENED: u32 = 0x80000000;
+// The app refuses to sign with keys outside `m/44'/309'`
+const CKB_PATH_PREFIX: [u32; 2] = [44 | HARDENED, 309 | HARDENED];
+const MAX_PATH_DEPTH: usize = 10;
+
+/// Parse a BIP32 path like `m/44'/309'/0'/0/0`, hardened indexes marked by `'` or `h`.
+pub fn parse_bip32_path(path: &str) -> Result<Vec<u32>, AppError> {
+    let invalid = || AppError::new(400).message(&format!("invalid BIP32 path {}", path));
+
+    let mut components = path.split('/');
+    if components.next() != Some("m") {
+        return Err(invalid());
+    }
+    let indexes = components
+        .map(|component| {
+            let (index, hardened) = match component.strip_suffix(['\'', 'h']) {
+                Some(index) => (index, true),
+                None => (component, false),
+            };
+            let index = index.parse::<u32>().map_err(|_| invalid())?;
+            if index >= HARDENED {
+                return Err(invalid());
+            }
+            Ok(if hardened { index | HARDENED } else { index })
+        })
+        .collect::<Result<Vec<u32>, AppError>>()?;
+
+    if indexes.len() > MAX_PATH_DEPTH || !indexes.starts_with(&CKB_PATH_PREFIX) {
+        return Err(AppError::new(400).message("BIP32 path must start with m/44'/309'"));
+    }
+    Ok(indexes)
+}
+
+/// The previous transaction of every input, in input order.
+pub async fn resolve_input_transactions(
+    tx: &TransactionView,
+) -> Result<Vec<Transaction>, AppError> {
+    let mut fetched: HashMap<H256, Transaction> = HashMap::new();
+    let mut input_txs = vec![];
+    for out_point in tx.input_pts_iter() {
+        let tx_hash: H256 = out_point.tx_hash().unpack();
+        if let Some(previous_tx) = fetched.get(&tx_hash) {
+            input_txs.push(previous_tx.clone());
+            continue;
+        }
+
+        let previous_tx = get_transaction_body(tx_hash.clone())
+            .await
+            .map_err(|err| {
+                AppError::new(500)
+                    .cause(err)
+                    .message("get transaction failed")
+            })?
+            .map(Transaction::from)
+            .ok_or(AppError::new(400).message("input transaction not found"))?;
+        fetched.insert(tx_hash, previous_tx.clone());
+        input_txs.push(previous_tx);
+    }
+    Ok(input_txs)
+}
+
+fn pack_bip32(path: &[u32]) -> Vec<u8> {
+    let mut data = (path.len() as u32).to_le_bytes().to_vec();
+    for index in path {
+        data.extend_from_slice(&index.to_le_bytes());
+    }
+    data
+}
+
+/// Molecule `AnnotatedTransaction` for the app's sign command. `input_txs` are the
+/// previous transactions of the inputs, in input order.
+pub fn annotated_transaction(
+    sign_path: &[u32],
+    change_path: &[u32],
+    tx: &TransactionView,
+    input_txs: &[Transaction],
+) -> Vec<u8> {
+    let raw = tx.data().raw();
+
+    let annotated_inputs = tx
+        .inputs()
+        .into_iter()
+        .zip(input_txs)
+        .map(|(input, source)| pack_table(&[input.as_slice(), source.raw().as_slice()]))
+        .collect::<Vec<Vec<u8>>>();
+    let annotated_inputs = pack_table(
+        &annotated_inputs
+            .iter()
+            .map(|input| input.as_slice())
+            .collect::<Vec<&[u8]>>(),
+    );
+    let annotated_raw = pack_table(&[
+        raw.version().as_slice(),
+        raw.cell_deps().as_slice(),
+        raw.header_deps().as_slice(),
+        &annotated_inputs,
+        raw.outputs().as_slice(),
+        raw.outputs_data().as_slice(),
+    ]);
+
+    pack_table(&[
+        &pack_bip32(sign_path),
+        &pack_bip32(change_path),
+        &(tx.inputs().len() as u32).to_le_bytes(),
+        &annotated_raw,
+        tx.witnesses().as_slice(),
+    ])
+}
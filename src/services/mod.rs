@@ -1,5 +1,21 @@
 pub mod address_book;
+pub mod cobuild;
 pub mod constants;
+pub mod decoder;
+pub mod email;
+pub mod events;
+pub mod export;
+pub mod ledger;
 pub mod multi_sig_account;
+pub mod multi_sig_message;
+pub mod neuron;
+pub mod notification;
+pub mod notification_channel;
+pub mod notifier;
 pub mod overrided;
+pub mod signature;
+pub mod transaction_comment;
+pub mod ur;
 pub mod user;
+pub mod webhook;
+pub mod worker;
@@ -1,46 +1,111 @@
 use std::collections::HashMap;
+use std::str::FromStr;
 
+use crate::config;
 use crate::models::multi_sig_invite::MultiSigInviteStatus;
 use crate::models::multi_sig_tx::{
-    CkbTransaction, TRANSACTION_STATUS_COMMITED, TRANSACTION_STATUS_FAILED,
-    TRANSACTION_STATUS_IN_PROGRESSING, TRANSACTION_STATUS_PENDING, TRANSACTION_STATUS_REJECT,
+    transaction_status_name, CkbSignature, CkbTransaction, TransactionHistory, TransactionLabel,
+    OPEN_TRANSACTION_STATUSES, PREFLIGHT_STATUS_FAILED, PREFLIGHT_STATUS_PASSED,
+    PREFLIGHT_STATUS_PENDING_SIGNATURES, PREFLIGHT_STATUS_UNAVAILABLE,
+    TRANSACTION_ACTION_CANCELLED, TRANSACTION_ACTION_EXECUTED, TRANSACTION_ACTION_REJECTED,
+    TRANSACTION_ACTION_RETRIED, TRANSACTION_ACTION_SIGNED, TRANSACTION_ERROR_NODE_REJECTED,
+    TRANSACTION_ERROR_TRANSPORT, TRANSACTION_STATUS_COMMITED, TRANSACTION_STATUS_FAILED,
+    TRANSACTION_STATUS_IN_PROGRESSING, TRANSACTION_STATUS_PENDING,
+    TRANSACTION_STATUS_READY_TO_EXECUTE, TRANSACTION_STATUS_REJECT, UNSENT_TRANSACTION_STATUSES,
 };
 use crate::repositories::address_book::AddressBookDao;
 use crate::repositories::ckb::{
-    add_signature_to_witness, get_ckb_network, get_live_cell, get_multisig_config,
-    get_multisig_script_hash, send_transaction,
+    add_signature_to_witness, estimate_cycles, get_block_timestamp, get_ckb_network,
+    get_fee_rate_statistics, get_live_cell, get_multisig_config, get_multisig_script_hash,
+    get_tip_block_number, get_transaction, get_transaction_body, get_transactions_by_lock,
+    get_tx_pool_info, parse_multisig_config, send_transaction, test_tx_pool_accept,
+    RPC_TRANSACTION_FAILED_TO_VERIFY,
 };
 use crate::repositories::db::DB_POOL;
+use crate::repositories::transaction_comment::TransactionCommentDao;
+use crate::serialize::ckb_cli::{CkbCliMultisigConfig, CkbCliTx};
+use crate::serialize::cobuild::{BuildingPacketReq, BuildingPacketRes};
+use crate::serialize::ledger::{LedgerContextFilters, LedgerSigningContext};
 use crate::serialize::multi_sig_account::{
-    InviteInfo, InviteStatusReq, ListSignerRes, MultiSigAccountUpdateReq, TransactionFilters,
-    UpdateTransactionStatusReq, UpdateTransactionStatusRes,
+    FeeRateEstimateRes, InviteInfo, InviteStatusReq, ListSignerRes, MultiSigAccountUpdateReq,
+    NewTransferReq, NewTransferRes, OnChainCancelReq, OnChainCancelRes, TransactionExportFilters,
+    TransactionFilters, TransactionLabelReq, UpdateTransactionStatusReq,
+    UpdateTransactionStatusRes,
 };
-use crate::serialize::transaction::{ListTransactionsRes, TransactionInfo, TransactionSumary};
+use crate::serialize::neuron::NeuronOfflineTx;
+use crate::serialize::transaction::{
+    AssetType, BundleMultisigConfig, DecodedOutput, ImportSignaturesRes, ListTransactionsRes,
+    PreflightVerdict, TransactionBundle, TransactionExportRow, TransactionInfo, TransactionSumary,
+};
+use crate::serialize::ur::UrSignRequestRes;
 use crate::serialize::PaginationRes;
+use crate::services::cobuild::{empty_message, BuildingPacket};
+use crate::services::decoder::{decode_outputs, decode_transaction, resolve_inputs};
+use crate::services::events::{
+    EventDispatcher, EVENT_INVITE_ACCEPTED, EVENT_INVITE_CREATED, EVENT_INVITE_REJECTED,
+    EVENT_PROPOSAL_CREATED, EVENT_SIGNATURE_ADDED, EVENT_THRESHOLD_REACHED,
+    EVENT_TRANSACTION_BROADCAST, EVENT_TRANSACTION_COMMITTED, EVENT_TRANSACTION_FAILED,
+    EVENT_TRANSACTION_REJECTED,
+};
+use crate::services::export::{
+    ExportFormat, ExportWriter, EXPORT_DIRECTION_INCOMING, EXPORT_DIRECTION_OUTGOING,
+};
+use crate::services::ledger::{
+    annotated_transaction, parse_bip32_path, resolve_input_transactions, DEFAULT_CHANGE_PATH,
+    DEFAULT_SIGN_PATH,
+};
+use crate::services::neuron::{from_neuron_transaction, to_neuron_transaction};
+use crate::services::signature::{
+    cobuild_message, ensure_witness_args, extract_witness_signatures, match_signer,
+    proposal_signing_message, resolve_input_cells,
+};
+use crate::services::ur::{
+    decode_ur, encode_ur, Cbor, UR_ORIGIN, UR_TYPE_SIGNATURE, UR_TYPE_SIGN_REQUEST,
+};
 use crate::{
     models::multi_sig_account::MultiSigInfo,
     repositories::multi_sig_account::MultiSigDao,
     serialize::{error::AppError, multi_sig_account::NewMultiSigAccountReq},
 };
 
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use ckb_jsonrpc_types::{JsonBytes, Status};
+use ckb_sdk::rpc::ckb_indexer::{CellType, Order, Tx};
 use ckb_sdk::Address;
 use ckb_sdk::AddressPayload;
 use ckb_types::bytes::Bytes;
-use ckb_types::core::{ScriptHashType, TransactionView};
-use ckb_types::packed::Transaction;
-use ckb_types::prelude::{IntoTransactionView, Pack, Unpack};
+use ckb_types::core::{Capacity, ScriptHashType, TransactionView};
+use ckb_types::packed::{CellOutput, Script, Transaction, WitnessArgs};
+use ckb_types::prelude::{Builder, Entity, IntoTransactionView, Pack, Unpack};
+use ckb_types::{H160, H256};
+use futures_util::{stream, Stream};
+
+const INPUTS_CONSUMED_REASON: &str = "Inputs consumed by another transaction";
+const MAX_MEMO_LENGTH: usize = 500;
+const MAX_LABELS: usize = 10;
+const MAX_LABEL_LENGTH: usize = 50;
+const EXPORT_PAGE_SIZE: i64 = 50;
 
 #[derive(Clone, Debug)]
 pub struct MultiSigSrv {
     multi_sig_dao: MultiSigDao,
     address_book_dao: AddressBookDao,
+    transaction_comment_dao: TransactionCommentDao,
+    event_dispatcher: EventDispatcher,
 }
 
 impl MultiSigSrv {
-    pub fn new(multi_sig_dao: MultiSigDao, address_book_dao: AddressBookDao) -> Self {
+    pub fn new(
+        multi_sig_dao: MultiSigDao,
+        address_book_dao: AddressBookDao,
+        transaction_comment_dao: TransactionCommentDao,
+        event_dispatcher: EventDispatcher,
+    ) -> Self {
         MultiSigSrv {
             multi_sig_dao: multi_sig_dao.clone(),
             address_book_dao: address_book_dao.clone(),
+            transaction_comment_dao: transaction_comment_dao.clone(),
+            event_dispatcher: event_dispatcher.clone(),
         }
     }
 
@@ -138,17 +203,7 @@ impl MultiSigSrv {
                         .message("invalid transaction json")
                 })?;
             let tx_view = Transaction::from(tx_info.clone().inner).into_view();
-            let first_output = tx_view.outputs().get(0).unwrap();
-            let lock = first_output.lock();
-            let address = Address::new(
-                get_ckb_network(),
-                AddressPayload::new_full(
-                    lock.hash_type().try_into().unwrap(),
-                    lock.code_hash(),
-                    lock.args().unpack(),
-                ),
-                true,
-            );
+            let decoded = decode_transaction(&tx.multi_sig_address, &tx_view).await;
 
             let signatures = self
                 .multi_sig_dao
@@ -162,6 +217,24 @@ impl MultiSigSrv {
                 .await
                 .unwrap();
 
+            let conflicts = self
+                .multi_sig_dao
+                .get_conflicting_txids(&tx.transaction_id, &OPEN_TRANSACTION_STATUSES)
+                .await
+                .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+
+            let label = self
+                .multi_sig_dao
+                .get_label(&tx.multi_sig_address, &tx.transaction_id)
+                .await
+                .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+
+            let comment_count = self
+                .transaction_comment_dao
+                .count_comments_by_txid(&tx.transaction_id)
+                .await
+                .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+
             let mut errors = None;
             if tx.status.eq(&TRANSACTION_STATUS_FAILED) {
                 errors = Some(
@@ -175,15 +248,34 @@ impl MultiSigSrv {
             results.push(TransactionInfo {
                 transaction_id: tx.clone().transaction_id,
                 multi_sig_address: tx.multi_sig_address,
-                to_address: address.to_string(),
                 confirmed: signatures
                     .iter()
                     .map(|sig| sig.signer_address.clone())
                     .collect(),
                 status: tx.status,
                 payload: tx.payload,
-                amount: first_output.capacity().unpack(),
+                decoded,
+                conflicts,
+                comment_count,
                 created_at: tx.created_at.timestamp(),
+                expires_at: tx
+                    .expires_at
+                    .map(|expires_at| expires_at.and_utc().timestamp()),
+                status_reason: tx.status_reason,
+                memo: tx.memo,
+                labels: label
+                    .as_ref()
+                    .map(|label| label.labels.clone())
+                    .unwrap_or_default(),
+                category: label.and_then(|label| label.category),
+                preflight: tx.preflight_status.map(|status| PreflightVerdict {
+                    status,
+                    message: tx.preflight_message,
+                    checked_at: tx
+                        .preflight_at
+                        .map(|preflight_at| preflight_at.and_utc().timestamp())
+                        .unwrap_or_default(),
+                }),
                 rejected: refusers
                     .iter()
                     .map(|sig| sig.signer_address.clone())
@@ -239,6 +331,7 @@ impl MultiSigSrv {
 
         let transaction = client.transaction().await.unwrap();
 
+        let mut invited: Vec<String> = vec![];
         let account_info: MultiSigInfo = match self
             .multi_sig_dao
             .create_new_account(
@@ -316,7 +409,7 @@ impl MultiSigSrv {
                 )
                 .await
             {
-                Ok(_) => continue,
+                Ok(_) => invited.push(signer.address.clone()),
                 Err(err) => {
                     transaction.rollback().await.unwrap();
                     return Err(AppError::new(500).message(&err.to_string()));
@@ -325,6 +418,17 @@ impl MultiSigSrv {
         }
 
         transaction.commit().await.unwrap();
+
+        for signer_address in invited {
+            self.event_dispatcher
+                .publish(
+                    EVENT_INVITE_CREATED,
+                    &account_info.multi_sig_address,
+                    None,
+                    Some(&signer_address),
+                )
+                .await;
+        }
         Ok(account_info)
     }
 
@@ -343,6 +447,20 @@ impl MultiSigSrv {
             return Err(AppError::new(500).message("Account not found."));
         }
 
+        if req.proposal_validity_hours.is_some_and(|hours| hours < 0) {
+            return Err(AppError::new(400).message("Proposal validity must not be negative"));
+        }
+
+        if let Some(fee_rate) = req.fee_rate {
+            let max_fee_rate: u64 = config::get("max_fee_rate");
+            if fee_rate <= 0 || fee_rate as u64 > max_fee_rate {
+                return Err(AppError::new(400).message(&format!(
+                    "Fee rate must be between 1 and {} shannons/KB",
+                    max_fee_rate
+                )));
+            }
+        }
+
         let mut info = multisig_info.unwrap();
         let signer = self
             .multi_sig_dao
@@ -364,6 +482,11 @@ impl MultiSigSrv {
         {
             true => {
                 info.name = req.clone().name;
+                info.fee_rate = req.fee_rate.unwrap_or(info.fee_rate);
+                info.auto_broadcast = req.auto_broadcast.unwrap_or(info.auto_broadcast);
+                if let Some(hours) = req.proposal_validity_hours {
+                    info.proposal_validity_hours = Some(hours).filter(|hours| *hours > 0);
+                }
                 Ok(info)
             }
             false => Err(AppError::new(500).message("Update account failed")),
@@ -403,6 +526,142 @@ impl MultiSigSrv {
         Ok(multi_sig_address)
     }
 
+    pub async fn estimate_fee_rate(
+        &self,
+        user_address: &str,
+        multisig_address: &str,
+    ) -> Result<FeeRateEstimateRes, AppError> {
+        let multi_sig_info = self
+            .request_multi_sig_info_for_signer(multisig_address, user_address)
+            .await?;
+
+        let pool_info = get_tx_pool_info().await.map_err(|err| {
+            AppError::new(500)
+                .cause(err)
+                .message("get tx pool info failed")
+        })?;
+        let min_fee_rate: u64 = pool_info.min_fee_rate.into();
+
+        // Fee rate statistics are empty on a quiet chain, fallback to the pool minimum
+        let (mean_fee_rate, median_fee_rate) =
+            match get_fee_rate_statistics(None).await.map_err(|err| {
+                AppError::new(500)
+                    .cause(err)
+                    .message("get fee rate statistics failed")
+            })? {
+                Some(statistics) => (statistics.mean.into(), statistics.median.into()),
+                None => (min_fee_rate, min_fee_rate),
+            };
+
+        Ok(FeeRateEstimateRes {
+            min_fee_rate,
+            max_fee_rate: config::get("max_fee_rate"),
+            mean_fee_rate: mean_fee_rate.max(min_fee_rate),
+            median_fee_rate: median_fee_rate.max(min_fee_rate),
+            default_fee_rate: (multi_sig_info.fee_rate as u64).max(min_fee_rate),
+        })
+    }
+
+    async fn validate_fee_rate(
+        &self,
+        multi_sig_info: &MultiSigInfo,
+        tx: &TransactionView,
+    ) -> Result<(), AppError> {
+        let decoded = decode_transaction(&multi_sig_info.multi_sig_address, tx).await;
+        let fee = decoded
+            .fee
+            .ok_or(AppError::new(400).message("invalid transaction - cannot resolve fee"))?;
+
+        // Measure the size with a full-length multisig lock, the same way the node will see it
+        let signed_tx = add_signature_to_witness(
+            multi_sig_info.threshold as usize,
+            tx,
+            &multi_sig_info.multi_sig_witness_data,
+            vec![],
+        )
+        .map_err(|err| AppError::new(400).cause(err).message("invalid witness"))?;
+        let tx_size = signed_tx.data().serialized_size_in_block() as u64;
+        let fee_rate = fee * 1000 / tx_size;
+
+        let pool_info = get_tx_pool_info().await.map_err(|err| {
+            AppError::new(500)
+                .cause(err)
+                .message("get tx pool info failed")
+        })?;
+        let min_fee_rate: u64 = pool_info.min_fee_rate.into();
+        if fee_rate < min_fee_rate {
+            return Err(AppError::new(400).message(&format!(
+                "Fee rate {} shannons/KB is below the pool minimum {}",
+                fee_rate, min_fee_rate
+            )));
+        }
+
+        let max_fee_rate: u64 = config::get("max_fee_rate");
+        if fee_rate > max_fee_rate {
+            return Err(AppError::new(400).message(&format!(
+                "Fee rate {} shannons/KB is above the limit {}",
+                fee_rate, max_fee_rate
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Run the transaction through the node's checks without submitting it and store the
+    /// verdict. Unsigned transactions carry a placeholder multisig lock, so only a failure of
+    /// that lock group is expected for them and leaves the check pending signatures.
+    async fn preflight_check(
+        &self,
+        multi_sig_info: &MultiSigInfo,
+        tx: &TransactionView,
+        signed: bool,
+    ) -> Result<Option<CkbTransaction>, AppError> {
+        let tx_id = hex::encode(tx.hash().raw_data());
+        let tx = if signed {
+            tx.clone()
+        } else {
+            add_signature_to_witness(
+                multi_sig_info.threshold as usize,
+                tx,
+                &multi_sig_info.multi_sig_witness_data,
+                vec![],
+            )
+            .map_err(|err| AppError::new(400).cause(err).message("invalid witness"))?
+        };
+
+        let (status, message) = if signed {
+            match test_tx_pool_accept(tx.data().into()).await {
+                Ok(_) => (PREFLIGHT_STATUS_PASSED, None),
+                Err(ckb_sdk::RpcError::Rpc(err)) => (PREFLIGHT_STATUS_FAILED, Some(err.message)),
+                Err(err) => (PREFLIGHT_STATUS_UNAVAILABLE, Some(err.to_string())),
+            }
+        } else {
+            let multi_sig_lock_hash = Script::from(
+                &Address::from_str(&multi_sig_info.multi_sig_address)
+                    .map_err(|_| AppError::new(500).message("invalid multisig address"))?,
+            )
+            .calc_script_hash();
+
+            // Running the scripts first surfaces missing deps and capacity errors even
+            // where the pool checks stop at the placeholder lock
+            let estimated = estimate_cycles(tx.data().into()).await.map(|_| ());
+            match unsigned_preflight_verdict(&tx, &multi_sig_lock_hash, estimated).await {
+                Some((PREFLIGHT_STATUS_PENDING_SIGNATURES, _)) | None => {
+                    let accepted = test_tx_pool_accept(tx.data().into()).await.map(|_| ());
+                    unsigned_preflight_verdict(&tx, &multi_sig_lock_hash, accepted)
+                        .await
+                        .unwrap_or((PREFLIGHT_STATUS_PASSED, None))
+                }
+                Some(verdict) => verdict,
+            }
+        };
+
+        self.multi_sig_dao
+            .update_preflight(&tx_id, status, &message)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))
+    }
+
     async fn validate_signer(
         &self,
         signer_address: &String,
@@ -439,29 +698,52 @@ impl MultiSigSrv {
         }
     }
 
+    /// Send the transaction to the node. A transport failure leaves the transaction
+    /// `Failed` and the confirmation tracker broadcasts it again with backoff, rejections
+    /// by the node are not retried.
     async fn broadcast_tx(
         &self,
         json_tx: ckb_jsonrpc_types::TransactionView,
     ) -> Result<(), AppError> {
-        let result: Result<ckb_types::H256, ckb_sdk::RpcError> =
-            send_transaction(json_tx.inner, None).await;
+        let tx_id = json_tx.hash.to_string();
+        let err = match send_transaction(json_tx.inner.clone(), None).await {
+            Ok(_) => return Ok(()),
+            Err(err) => err,
+        };
 
-        if let Err(err) = result {
-            let tx_id = json_tx.hash.to_string();
-            self.save_transaction_error("sendTransaction", &tx_id, &err.to_string())
-                .await;
-            return Err(AppError::new(500).cause(err).message("Submit tx failed"));
+        let transport_error = !matches!(err, ckb_sdk::RpcError::Rpc(_));
+        // An earlier broadcast may have reached the node before the connection dropped
+        if !transport_error && self.known_by_node(&json_tx.hash).await {
+            return Ok(());
         }
 
-        Ok(())
+        let category = if transport_error {
+            TRANSACTION_ERROR_TRANSPORT
+        } else {
+            TRANSACTION_ERROR_NODE_REJECTED
+        };
+        self.save_categorized_error("sendTransaction", &tx_id, Some(category), &err.to_string())
+            .await;
+        Err(AppError::new(500).cause(err).message("Submit tx failed"))
+    }
+
+    async fn known_by_node(&self, tx_hash: &H256) -> bool {
+        matches!(
+            get_transaction(tx_hash.clone()).await,
+            Ok(Some(tx_with_status))
+                if matches!(
+                    tx_with_status.tx_status.status,
+                    Status::Pending | Status::Proposed | Status::Committed
+                )
+        )
     }
 
     pub async fn create_new_transfer(
         &self,
         signer_address: &String,
-        signature: &String,
-        payload: &String,
-    ) -> Result<CkbTransaction, AppError> {
+        req: NewTransferReq,
+    ) -> Result<NewTransferRes, AppError> {
+        let payload = &req.payload;
         let tx_info: ckb_jsonrpc_types::TransactionView = serde_json::from_str(payload.as_str())
             .map_err(|err| {
                 AppError::new(400)
@@ -470,6 +752,7 @@ impl MultiSigSrv {
             })?;
         let tx: TransactionView = Transaction::from(tx_info.clone().inner).into_view();
         let tx_id = tx_info.hash.to_string();
+        ensure_witness_args(&tx)?;
 
         let outpoints: Vec<ckb_jsonrpc_types::OutPoint> = tx
             .input_pts_iter()
@@ -484,22 +767,84 @@ impl MultiSigSrv {
             .await?;
         let multi_sig_info = self.request_multi_sig_info(&multi_sig_address).await?;
 
+        if req
+            .memo
+            .as_ref()
+            .is_some_and(|memo| memo.chars().count() > MAX_MEMO_LENGTH)
+        {
+            return Err(AppError::new(400).message(&format!(
+                "Memo must not exceed {} characters",
+                MAX_MEMO_LENGTH
+            )));
+        }
+
+        // Reject proposals the pool won't accept before anyone signs them
+        self.validate_fee_rate(&multi_sig_info, &tx).await?;
+
+        let expires_at = match req.expires_at {
+            Some(expires_at) => {
+                let expires_at = DateTime::from_timestamp(expires_at, 0)
+                    .ok_or(AppError::new(400).message("invalid expires_at"))?
+                    .naive_utc();
+                if expires_at <= Utc::now().naive_utc() {
+                    return Err(AppError::new(400).message("expires_at must be in the future"));
+                }
+                Some(expires_at)
+            }
+            None => multi_sig_info
+                .proposal_validity_hours
+                .map(|hours| Utc::now().naive_utc() + Duration::hours(hours as i64)),
+        };
+
+        let inputs: Vec<(String, i32)> = tx
+            .input_pts_iter()
+            .map(|out_point| {
+                let index: u32 = out_point.index().unpack();
+                (hex::encode(out_point.tx_hash().raw_data()), index as i32)
+            })
+            .collect();
+
         let ckb_tx = self
             .multi_sig_dao
             .create_new_transfer(
                 &multi_sig_address.to_string(),
                 &tx_id,
-                payload,
+                &inputs,
+                &expires_at,
                 signer_address,
-                signature,
+                &req,
+            )
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        self.event_dispatcher
+            .publish(
+                EVENT_PROPOSAL_CREATED,
+                &multi_sig_address.to_string(),
+                Some(&tx_id),
+                Some(signer_address),
             )
+            .await;
+
+        // Flag other open proposals spending the same cells
+        let conflicts = self
+            .multi_sig_dao
+            .get_conflicting_txids(&tx_id, &OPEN_TRANSACTION_STATUSES)
             .await
             .map_err(|err| AppError::new(500).message(&err.to_string()))?;
 
+        // Let signers see early whether the node would accept the transaction
+        let ckb_tx = self
+            .preflight_check(&multi_sig_info, &tx, false)
+            .await?
+            .unwrap_or(ckb_tx);
+
         // check if threshold is one => broadcast tx immediately
         let _ = self.check_threshold(&multi_sig_info, &tx).await;
 
-        Ok(ckb_tx)
+        Ok(NewTransferRes {
+            transaction: ckb_tx,
+            conflicts,
+        })
     }
 
     async fn check_threshold(
@@ -509,172 +854,1947 @@ impl MultiSigSrv {
     ) -> Result<(), AppError> {
         let tx_id = hex::encode(tx.hash().raw_data());
 
-        // check if threshold is reached => broadcast tx
-        let ckb_signatures = self
+        // check if threshold is reached => claim the tx, the row lock keeps signature
+        // revocations out until the status has moved on. Accounts without auto broadcast
+        // wait for an explicit execute.
+        let next_status = if multi_sig_info.auto_broadcast {
+            TRANSACTION_STATUS_IN_PROGRESSING
+        } else {
+            TRANSACTION_STATUS_READY_TO_EXECUTE
+        };
+        let ckb_signatures = match self
             .multi_sig_dao
-            .get_list_signatures_by_txid(&tx_id)
+            .claim_signed_transaction(
+                &tx_id,
+                multi_sig_info.threshold,
+                TRANSACTION_STATUS_PENDING,
+                next_status,
+            )
             .await
-            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
-        if ckb_signatures
-            .len()
-            .ge(&(multi_sig_info.threshold as usize))
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
         {
-            let signatures = ckb_signatures
-                .iter()
-                .map(|s| Bytes::from(hex::decode(s.signature.clone()).unwrap()))
-                .collect();
-
-            // Add Signatures to witness
-            let tx = add_signature_to_witness(
-                multi_sig_info.threshold as usize,
-                tx,
-                &multi_sig_info.multi_sig_witness_data,
-                signatures,
+            Some(ckb_signatures) => ckb_signatures,
+            None => return Ok(()),
+        };
+        self.event_dispatcher
+            .publish(
+                EVENT_THRESHOLD_REACHED,
+                &multi_sig_info.multi_sig_address,
+                Some(&tx_id),
+                None,
             )
-            .map_err(|err| {
-                AppError::new(500)
-                    .cause(err)
-                    .message("add signature to witness failed")
-            })?;
+            .await;
+
+        if !multi_sig_info.auto_broadcast {
+            return Ok(());
+        }
+
+        self.broadcast_signed_tx(
+            multi_sig_info,
+            tx,
+            &ckb_signatures,
+            TRANSACTION_STATUS_PENDING,
+        )
+        .await
+    }
+
+    /// Assemble the witnesses of a claimed transaction and broadcast it. The transaction
+    /// is put back to `release_status` when its witnesses can not be assembled.
+    async fn broadcast_signed_tx(
+        &self,
+        multi_sig_info: &MultiSigInfo,
+        tx: &TransactionView,
+        ckb_signatures: &[CkbSignature],
+        release_status: i16,
+    ) -> Result<(), AppError> {
+        let tx_id = hex::encode(tx.hash().raw_data());
+        let signatures = ckb_signatures
+            .iter()
+            .map(|s| Bytes::from(hex::decode(s.signature.clone()).unwrap()))
+            .collect();
 
-            let json_tx = ckb_jsonrpc_types::TransactionView::from(tx);
-            self.broadcast_tx(json_tx.clone()).await?;
+        // Add Signatures to witness
+        let result = add_signature_to_witness(
+            multi_sig_info.threshold as usize,
+            tx,
+            &multi_sig_info.multi_sig_witness_data,
+            signatures,
+        );
+        let tx = match result {
+            Ok(tx) => tx,
+            Err(err) => {
+                self.release_broadcast_claim(&tx_id, release_status).await;
+                return Err(AppError::new(500)
+                    .cause(err)
+                    .message("add signature to witness failed"));
+            }
+        };
 
-            self.sync_status_after_broadcast(
+        // Don't send what the node would reject, the transport is checked by broadcast_tx.
+        // AppError isn't Send, only its text is kept across the await the workers run.
+        let verdict = self
+            .preflight_check(multi_sig_info, &tx, true)
+            .await
+            .map_err(|err| (err.status, err.to_string()));
+        let verdict = match verdict {
+            Ok(verdict) => verdict,
+            Err((status, message)) => {
+                self.release_broadcast_claim(&tx_id, release_status).await;
+                return Err(AppError::new(status).message(&message));
+            }
+        };
+        if let Some(verdict) = verdict
+            .filter(|verdict| verdict.preflight_status.as_deref() == Some(PREFLIGHT_STATUS_FAILED))
+        {
+            let message = verdict.preflight_message.unwrap_or_default();
+            self.save_categorized_error(
+                "preflight",
                 &tx_id,
-                &serde_json::to_string_pretty(&json_tx).unwrap(),
+                Some(TRANSACTION_ERROR_NODE_REJECTED),
+                &message,
             )
-            .await?;
+            .await;
+            return Err(
+                AppError::new(400).message(&format!("Pre-flight check failed: {}", message))
+            );
         }
 
+        // A failed broadcast leaves the transaction `Failed`, it can be retried later
+        let json_tx = ckb_jsonrpc_types::TransactionView::from(tx);
+        self.broadcast_tx(json_tx.clone()).await?;
+
+        self.sync_status_after_broadcast(&tx_id, &serde_json::to_string_pretty(&json_tx).unwrap())
+            .await?;
+        self.event_dispatcher
+            .publish(
+                EVENT_TRANSACTION_BROADCAST,
+                &multi_sig_info.multi_sig_address,
+                Some(&tx_id),
+                None,
+            )
+            .await;
+
         Ok(())
     }
 
-    pub async fn submit_signature(
+    async fn release_broadcast_claim(&self, txid: &String, status: i16) {
+        let _ = self
+            .multi_sig_dao
+            .update_transaction_status(txid, status)
+            .await;
+    }
+
+    /// Broadcast a proposal which collected its signatures on an account without
+    /// auto broadcast. Any member of the account can execute it.
+    pub async fn execute_transaction(
         &self,
-        signer_address: &String,
-        signature: &str,
+        signer_address: &str,
         txid: &str,
     ) -> Result<CkbTransaction, AppError> {
         let transaction = self
             .multi_sig_dao
             .get_tx_by_hash_and_signer(signer_address, txid)
             .await
-            .unwrap();
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Transaction not found"))?;
 
-        if transaction.is_none() {
-            return Err(AppError::new(404).message("Transaction not found"));
+        if transaction.status.ne(&TRANSACTION_STATUS_READY_TO_EXECUTE) {
+            return Err(AppError::new(400).message("Transaction is not ready to execute"));
         }
 
-        let ckb_tx = transaction.unwrap();
-        if ckb_tx.status.ne(&TRANSACTION_STATUS_PENDING) {
-            return Err(AppError::new(404).message("Transaction not valid"));
+        if transaction
+            .expires_at
+            .is_some_and(|expires_at| expires_at < Utc::now().naive_utc())
+        {
+            self.expire_transaction(
+                &transaction.transaction_id,
+                "Proposal expired",
+                &UNSENT_TRANSACTION_STATUSES,
+            )
+            .await?;
+            return Err(AppError::new(400).message("Transaction expired"));
         }
 
         let tx_info: ckb_jsonrpc_types::TransactionView =
-            serde_json::from_str(ckb_tx.payload.as_str()).map_err(|err| {
+            serde_json::from_str(transaction.payload.as_str()).map_err(|err| {
                 AppError::new(400)
                     .cause(err)
                     .message("invalid transaction json")
             })?;
+        let tx = Transaction::from(tx_info.inner).into_view();
 
-        let tx = Transaction::from(tx_info.clone().inner).into_view();
-        let tx_id = tx_info.hash.to_string();
-
-        let outpoints: Vec<ckb_jsonrpc_types::OutPoint> = tx
-            .input_pts_iter()
-            .map(ckb_jsonrpc_types::OutPoint::from)
-            .collect();
-
-        // validate outpoints status from CKB node
-        // it should be check threshold on cells instead of checking by tx
-        // currently all cells is belong to single multi-sig address so we able
-        // to use check threshold by txid
-        let validate_outputs_result = self.validate_outpoints(&outpoints).await;
-        if let Err(err) = validate_outputs_result {
-            self.save_transaction_error(signer_address, &tx_id, &err.to_string())
-                .await;
-            return Err(AppError::new(500).message(&err.to_string()));
-        }
-
-        let multi_sig_address = validate_outputs_result.unwrap();
-
-        // Validate if user is one of multi-sig signers
-        if let Err(err) = self
-            .validate_signer(signer_address, &multi_sig_address)
-            .await
-        {
-            self.save_transaction_error(signer_address, &tx_id, &err.to_string())
-                .await;
-            return Err(err);
+        if self.inputs_consumed(&tx).await? {
+            self.expire_transaction(
+                &transaction.transaction_id,
+                INPUTS_CONSUMED_REASON,
+                &UNSENT_TRANSACTION_STATUSES,
+            )
+            .await?;
+            return Err(AppError::new(400).message("Transaction expired - inputs consumed"));
         }
 
-        let multi_sig_info = self.request_multi_sig_info(&multi_sig_address).await?;
-
-        let ckb_tx: CkbTransaction = self
+        let multi_sig_info = self
+            .request_multi_sig_info(&transaction.multi_sig_address)
+            .await?;
+        let ckb_signatures = self
             .multi_sig_dao
-            .add_signature(
-                &tx_id,
-                &multi_sig_address,
-                &ckb_tx.payload,
-                signer_address,
-                &signature.to_owned(),
+            .claim_signed_transaction(
+                &transaction.transaction_id,
+                multi_sig_info.threshold,
+                TRANSACTION_STATUS_READY_TO_EXECUTE,
+                TRANSACTION_STATUS_IN_PROGRESSING,
             )
             .await
-            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(409).message("Transaction changed, please try again"))?;
+
+        self.broadcast_signed_tx(
+            &multi_sig_info,
+            &tx,
+            &ckb_signatures,
+            TRANSACTION_STATUS_READY_TO_EXECUTE,
+        )
+        .await?;
+        self.record_history(txid, signer_address, TRANSACTION_ACTION_EXECUTED)
+            .await;
 
-        // Check threshold sig
-        self.check_threshold(&multi_sig_info, &tx).await?;
-        Ok(ckb_tx)
+        self.multi_sig_dao
+            .get_tx_by_hash(&transaction.transaction_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Transaction not found"))
     }
 
-    pub async fn reject_transaction(
+    /// Rebroadcast a failed transaction on behalf of a member of the account.
+    pub async fn retry_transaction(
         &self,
         signer_address: &str,
         txid: &str,
-    ) -> Result<bool, AppError> {
-        match self
+    ) -> Result<CkbTransaction, AppError> {
+        let transaction = self
             .multi_sig_dao
             .get_tx_by_hash_and_signer(signer_address, txid)
             .await
-            .unwrap()
-        {
-            Some(transaction) => {
-                if let Some(multisig_info) = self
-                    .multi_sig_dao
-                    .request_multi_sig_info(&transaction.multi_sig_address)
-                    .await
-                    .map_err(|err| AppError::new(500).message(&err.to_string()))
-                    .unwrap()
-                {
-                    self.multi_sig_dao
-                        .reject_transaction(&txid.to_owned(), &signer_address.to_owned())
-                        .await
-                        .unwrap();
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Transaction not found"))?;
 
-                    let refusers = self
-                        .multi_sig_dao
-                        .get_list_rejected_by_txid(&txid.to_owned())
-                        .await
-                        .map_err(|err| AppError::new(500).message(&err.to_string()))
-                        .unwrap();
+        if transaction.status.ne(&TRANSACTION_STATUS_FAILED) {
+            return Err(AppError::new(400).message("Only failed transactions can be retried"));
+        }
 
-                    let max_valid_signers = multisig_info.signers - (refusers.len() as i16);
+        self.rebroadcast_failed_transaction(&transaction, Some(signer_address))
+            .await?;
 
-                    if max_valid_signers < multisig_info.threshold {
-                        let _ = self
-                            .multi_sig_dao
-                            .update_transaction_status(&txid.to_owned(), TRANSACTION_STATUS_REJECT)
-                            .await
+        self.multi_sig_dao
+            .get_tx_by_hash(&transaction.transaction_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Transaction not found"))
+    }
+
+    /// Rebroadcast the failed transactions which could not reach the node, until
+    /// `broadcast_retry_attempts` broadcasts failed. The delay doubles after each failure.
+    pub async fn retry_transport_failures(&self) -> Result<(), AppError> {
+        let max_attempts: i64 = config::get("broadcast_retry_attempts");
+        let backoff_secs: i32 = config::get("broadcast_retry_backoff_secs");
+        let transactions = self
+            .multi_sig_dao
+            .get_txs_due_for_rebroadcast(
+                &TRANSACTION_ERROR_TRANSPORT.to_owned(),
+                max_attempts,
+                backoff_secs,
+            )
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+
+        for transaction in transactions {
+            if let Err(err) = self
+                .rebroadcast_failed_transaction(&transaction, None)
+                .await
+            {
+                log::warn!(
+                    "rebroadcast transaction {} failed: {}",
+                    transaction.transaction_id,
+                    err
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Assemble the witnesses of a failed transaction again from the stored signatures and
+    /// broadcast it once the inputs are confirmed to be still live.
+    async fn rebroadcast_failed_transaction(
+        &self,
+        transaction: &CkbTransaction,
+        retried_by: Option<&str>,
+    ) -> Result<(), AppError> {
+        if transaction
+            .expires_at
+            .is_some_and(|expires_at| expires_at < Utc::now().naive_utc())
+        {
+            self.expire_transaction(
+                &transaction.transaction_id,
+                "Proposal expired",
+                &[TRANSACTION_STATUS_FAILED],
+            )
+            .await?;
+            return Err(AppError::new(400).message("Transaction expired"));
+        }
+
+        let tx_info: ckb_jsonrpc_types::TransactionView =
+            serde_json::from_str(transaction.payload.as_str()).map_err(|err| {
+                AppError::new(400)
+                    .cause(err)
+                    .message("invalid transaction json")
+            })?;
+        let tx = Transaction::from(tx_info.inner).into_view();
+
+        if self.inputs_consumed(&tx).await? {
+            self.expire_transaction(
+                &transaction.transaction_id,
+                INPUTS_CONSUMED_REASON,
+                &[TRANSACTION_STATUS_FAILED],
+            )
+            .await?;
+            return Err(AppError::new(400).message("Transaction expired - inputs consumed"));
+        }
+
+        let multi_sig_info = self
+            .request_multi_sig_info(&transaction.multi_sig_address)
+            .await?;
+        let signatures = self
+            .multi_sig_dao
+            .get_list_signatures_by_txid(&transaction.transaction_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        if signatures.len() < multi_sig_info.threshold as usize {
+            return Err(AppError::new(400).message("Transaction has not reached its threshold"));
+        }
+
+        let ckb_signatures = self
+            .multi_sig_dao
+            .claim_signed_transaction(
+                &transaction.transaction_id,
+                multi_sig_info.threshold,
+                TRANSACTION_STATUS_FAILED,
+                TRANSACTION_STATUS_IN_PROGRESSING,
+            )
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(409).message("Transaction changed, please try again"))?;
+
+        if let Some(signer_address) = retried_by {
+            self.record_history(
+                &transaction.transaction_id,
+                signer_address,
+                TRANSACTION_ACTION_RETRIED,
+            )
+            .await;
+        }
+        self.broadcast_signed_tx(
+            &multi_sig_info,
+            &tx,
+            &ckb_signatures,
+            TRANSACTION_STATUS_FAILED,
+        )
+        .await
+    }
+
+    pub async fn submit_signature(
+        &self,
+        signer_address: &String,
+        signature: &str,
+        txid: &str,
+    ) -> Result<CkbTransaction, AppError> {
+        let transaction = self
+            .multi_sig_dao
+            .get_tx_by_hash_and_signer(signer_address, txid)
+            .await
+            .unwrap();
+
+        if transaction.is_none() {
+            return Err(AppError::new(404).message("Transaction not found"));
+        }
+
+        let ckb_tx = transaction.unwrap();
+        if ckb_tx.status.ne(&TRANSACTION_STATUS_PENDING) {
+            return Err(AppError::new(404).message("Transaction not valid"));
+        }
+
+        if ckb_tx
+            .expires_at
+            .is_some_and(|expires_at| expires_at < Utc::now().naive_utc())
+        {
+            self.expire_transaction(
+                &ckb_tx.transaction_id,
+                "Proposal expired",
+                &UNSENT_TRANSACTION_STATUSES,
+            )
+            .await?;
+            return Err(AppError::new(400).message("Transaction expired"));
+        }
+
+        let tx_info: ckb_jsonrpc_types::TransactionView =
+            serde_json::from_str(ckb_tx.payload.as_str()).map_err(|err| {
+                AppError::new(400)
+                    .cause(err)
+                    .message("invalid transaction json")
+            })?;
+
+        let tx = Transaction::from(tx_info.clone().inner).into_view();
+        let tx_id = tx_info.hash.to_string();
+
+        if self.inputs_consumed(&tx).await? {
+            self.expire_transaction(&tx_id, INPUTS_CONSUMED_REASON, &UNSENT_TRANSACTION_STATUSES)
+                .await?;
+            return Err(AppError::new(400).message("Transaction expired - inputs consumed"));
+        }
+
+        let outpoints: Vec<ckb_jsonrpc_types::OutPoint> = tx
+            .input_pts_iter()
+            .map(ckb_jsonrpc_types::OutPoint::from)
+            .collect();
+
+        // validate outpoints status from CKB node
+        // it should be check threshold on cells instead of checking by tx
+        // currently all cells is belong to single multi-sig address so we able
+        // to use check threshold by txid
+        let validate_outputs_result = self.validate_outpoints(&outpoints).await;
+        if let Err(err) = validate_outputs_result {
+            self.save_transaction_error(signer_address, &tx_id, &err.to_string())
+                .await;
+            return Err(AppError::new(500).message(&err.to_string()));
+        }
+
+        let multi_sig_address = validate_outputs_result.unwrap();
+
+        // Validate if user is one of multi-sig signers
+        if let Err(err) = self
+            .validate_signer(signer_address, &multi_sig_address)
+            .await
+        {
+            self.save_transaction_error(signer_address, &tx_id, &err.to_string())
+                .await;
+            return Err(err);
+        }
+
+        let multi_sig_info = self.request_multi_sig_info(&multi_sig_address).await?;
+
+        let ckb_tx: CkbTransaction = self
+            .multi_sig_dao
+            .add_signature(&tx_id, signer_address, &signature.to_owned())
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        self.record_history(&tx_id, signer_address, TRANSACTION_ACTION_SIGNED)
+            .await;
+        self.event_dispatcher
+            .publish(
+                EVENT_SIGNATURE_ADDED,
+                &multi_sig_info.multi_sig_address,
+                Some(&tx_id),
+                Some(signer_address),
+            )
+            .await;
+
+        // Check threshold sig
+        self.check_threshold(&multi_sig_info, &tx).await?;
+        Ok(ckb_tx)
+    }
+
+    pub async fn reject_transaction(
+        &self,
+        signer_address: &str,
+        txid: &str,
+    ) -> Result<bool, AppError> {
+        match self
+            .multi_sig_dao
+            .get_tx_by_hash_and_signer(signer_address, txid)
+            .await
+            .unwrap()
+        {
+            Some(transaction) => {
+                if let Some(multisig_info) = self
+                    .multi_sig_dao
+                    .request_multi_sig_info(&transaction.multi_sig_address)
+                    .await
+                    .map_err(|err| AppError::new(500).message(&err.to_string()))
+                    .unwrap()
+                {
+                    self.multi_sig_dao
+                        .reject_transaction(&txid.to_owned(), &signer_address.to_owned())
+                        .await
+                        .unwrap();
+                    self.record_history(txid, signer_address, TRANSACTION_ACTION_REJECTED)
+                        .await;
+
+                    let refusers = self
+                        .multi_sig_dao
+                        .get_list_rejected_by_txid(&txid.to_owned())
+                        .await
+                        .map_err(|err| AppError::new(500).message(&err.to_string()))
+                        .unwrap();
+
+                    let max_valid_signers = multisig_info.signers - (refusers.len() as i16);
+
+                    if max_valid_signers < multisig_info.threshold {
+                        let _ = self
+                            .multi_sig_dao
+                            .update_transaction_status(&txid.to_owned(), TRANSACTION_STATUS_REJECT)
+                            .await
                             .map_err(|err| AppError::new(500).message(&err.to_string()));
+                        self.event_dispatcher
+                            .publish(
+                                EVENT_TRANSACTION_REJECTED,
+                                &multisig_info.multi_sig_address,
+                                Some(txid),
+                                Some(signer_address),
+                            )
+                            .await;
                     }
 
-                    return Ok(true);
-                }
-                Err(AppError::new(404).message("Account not found"))
+                    return Ok(true);
+                }
+                Err(AppError::new(404).message("Account not found"))
+            }
+            None => Err(AppError::new(404).message("Transaction not found")),
+        }
+    }
+
+    /// Whether an input is no longer live. The node reports spent cells as `unknown`, so
+    /// anything but `live` counts as consumed.
+    async fn inputs_consumed(&self, tx: &TransactionView) -> Result<bool, AppError> {
+        for out_point in tx.input_pts_iter() {
+            let cell_with_status = get_live_cell(out_point.into(), false)
+                .await
+                .map_err(|err| {
+                    AppError::new(500)
+                        .cause(err)
+                        .message("get live cell failed")
+                })?;
+            if cell_with_status.status.ne("live") {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    async fn expire_transaction(
+        &self,
+        txid: &str,
+        reason: &str,
+        statuses: &[i16],
+    ) -> Result<bool, AppError> {
+        self.multi_sig_dao
+            .expire_transaction(&txid.to_owned(), &reason.to_owned(), statuses)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))
+    }
+
+    /// Move unsent proposals which are past their deadline, or whose inputs were spent
+    /// by another transaction, to `Expired`.
+    pub async fn expire_pending_transactions(&self) -> Result<(), AppError> {
+        self.multi_sig_dao
+            .expire_overdue_transactions(&"Proposal expired".to_owned())
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+
+        let mut transactions = vec![];
+        for status in UNSENT_TRANSACTION_STATUSES {
+            transactions.extend(
+                self.multi_sig_dao
+                    .get_txs_by_status(status)
+                    .await
+                    .map_err(|err| AppError::new(500).message(&err.to_string()))?,
+            );
+        }
+
+        for transaction in transactions {
+            let tx_info: ckb_jsonrpc_types::TransactionView =
+                match serde_json::from_str(transaction.payload.as_str()) {
+                    Ok(tx_info) => tx_info,
+                    Err(_) => continue,
+                };
+            let tx = Transaction::from(tx_info.inner).into_view();
+
+            // AppError is not Send, don't hold it across the await below
+            let consumed = match self.inputs_consumed(&tx).await {
+                Ok(consumed) => consumed,
+                Err(err) => {
+                    log::warn!(
+                        "check inputs of {} failed: {}",
+                        transaction.transaction_id,
+                        err
+                    );
+                    continue;
+                }
+            };
+
+            if consumed {
+                self.expire_transaction(
+                    &transaction.transaction_id,
+                    INPUTS_CONSUMED_REASON,
+                    &UNSENT_TRANSACTION_STATUSES,
+                )
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn cancel_transaction(
+        &self,
+        signer_address: &str,
+        txid: &str,
+    ) -> Result<bool, AppError> {
+        let transaction = self
+            .multi_sig_dao
+            .get_tx_by_hash_and_signer(signer_address, txid)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Transaction not found"))?;
+
+        if transaction.proposer_address.as_deref() != Some(signer_address) {
+            return Err(AppError::new(403).message("Only the proposer can cancel this transaction"));
+        }
+
+        if transaction.status.ne(&TRANSACTION_STATUS_PENDING) {
+            return Err(AppError::new(400).message("Transaction not valid"));
+        }
+
+        let multi_sig_info = self
+            .request_multi_sig_info(&transaction.multi_sig_address)
+            .await?;
+        let signatures = self
+            .multi_sig_dao
+            .get_list_signatures_by_txid(&transaction.transaction_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        if signatures.len() >= multi_sig_info.threshold as usize {
+            return Err(AppError::new(400)
+                .message("Transaction has reached its threshold, cancel it on-chain instead"));
+        }
+
+        // The checks above are repeated by the DAO under a row lock
+        let cancelled = self
+            .multi_sig_dao
+            .cancel_transaction(&transaction.transaction_id, multi_sig_info.threshold)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        if !cancelled {
+            return Err(AppError::new(409).message("Transaction changed, please try again"));
+        }
+        self.record_history(txid, signer_address, TRANSACTION_ACTION_CANCELLED)
+            .await;
+
+        Ok(true)
+    }
+
+    pub async fn revoke_signature(
+        &self,
+        signer_address: &str,
+        txid: &str,
+    ) -> Result<bool, AppError> {
+        let transaction = self
+            .multi_sig_dao
+            .get_tx_by_hash_and_signer(signer_address, txid)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Transaction not found"))?;
+
+        if transaction.status.ne(&TRANSACTION_STATUS_PENDING) {
+            return Err(AppError::new(400).message("Transaction not valid"));
+        }
+
+        let signatures = self
+            .multi_sig_dao
+            .get_list_signatures_by_txid(&transaction.transaction_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        if !signatures
+            .iter()
+            .any(|s| s.signer_address == signer_address)
+        {
+            return Err(AppError::new(404).message("Signature not found"));
+        }
+
+        let multi_sig_info = self
+            .request_multi_sig_info(&transaction.multi_sig_address)
+            .await?;
+        if signatures.len() >= multi_sig_info.threshold as usize {
+            return Err(AppError::new(400).message("Transaction has reached its threshold"));
+        }
+
+        // The checks above are repeated by the DAO under a row lock
+        let revoked = self
+            .multi_sig_dao
+            .revoke_signature(
+                &transaction.transaction_id,
+                &signer_address.to_owned(),
+                multi_sig_info.threshold,
+            )
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        if !revoked {
+            return Err(AppError::new(409).message("Transaction changed, please try again"));
+        }
+
+        Ok(true)
+    }
+
+    pub async fn request_transaction_history(
+        &self,
+        signer_address: &str,
+        txid: &str,
+    ) -> Result<Vec<TransactionHistory>, AppError> {
+        let transaction = self
+            .multi_sig_dao
+            .get_tx_by_hash_and_signer(signer_address, txid)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Transaction not found"))?;
+
+        self.multi_sig_dao
+            .get_histories_by_txid(&transaction.transaction_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))
+    }
+
+    async fn record_history(&self, txid: &str, actor_address: &str, action: &str) {
+        let _ = self
+            .multi_sig_dao
+            .add_history(&txid.to_owned(), &actor_address.to_owned(), action)
+            .await;
+    }
+
+    /// Build a self-transfer spending the inputs of a fully signed proposal. Once
+    /// it is committed the signed payload of the original proposal can not be replayed.
+    pub async fn build_onchain_cancellation(
+        &self,
+        signer_address: &str,
+        txid: &str,
+    ) -> Result<OnChainCancelRes, AppError> {
+        let (transaction, multi_sig_info, tx) =
+            self.request_signed_proposal(signer_address, txid).await?;
+
+        let mut total_capacity: u64 = 0;
+        for cell in resolve_inputs(&tx).await {
+            let (output, _) =
+                cell.ok_or(AppError::new(500).message("cannot resolve transaction inputs"))?;
+            if output.type_().to_opt().is_some() {
+                return Err(AppError::new(400)
+                    .message("On-chain cancellation only supports plain CKB inputs"));
+            }
+            let capacity: u64 = output.capacity().unpack();
+            total_capacity += capacity;
+        }
+
+        let multi_sig_address = Address::from_str(&multi_sig_info.multi_sig_address)
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        let output = CellOutput::new_builder()
+            .lock(Script::from(&multi_sig_address))
+            .capacity(total_capacity.pack())
+            .build();
+        let witnesses: Vec<ckb_types::packed::Bytes> = vec![Default::default(); tx.inputs().len()];
+        let cancel_tx = TransactionView::new_advanced_builder()
+            .cell_deps(tx.cell_deps())
+            .header_deps(tx.header_deps())
+            .inputs(tx.inputs())
+            .output(output.clone())
+            .output_data(Bytes::new().pack())
+            .set_witnesses(witnesses)
+            .build();
+
+        // Fee is paid on the size with a full-length multisig lock
+        let cancel_tx = add_signature_to_witness(
+            multi_sig_info.threshold as usize,
+            &cancel_tx,
+            &multi_sig_info.multi_sig_witness_data,
+            vec![],
+        )
+        .map_err(|err| AppError::new(500).cause(err).message("invalid witness"))?;
+        let pool_info = get_tx_pool_info().await.map_err(|err| {
+            AppError::new(500)
+                .cause(err)
+                .message("get tx pool info failed")
+        })?;
+        let min_fee_rate: u64 = pool_info.min_fee_rate.into();
+        let fee_rate = (multi_sig_info.fee_rate as u64).max(min_fee_rate);
+        let tx_size = cancel_tx.data().serialized_size_in_block() as u64;
+        let fee = (tx_size * fee_rate).div_ceil(1000);
+
+        let occupied_capacity = output
+            .occupied_capacity(Capacity::zero())
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .as_u64();
+        let capacity = total_capacity
+            .checked_sub(fee)
+            .filter(|capacity| *capacity >= occupied_capacity)
+            .ok_or(AppError::new(400).message("Inputs capacity is not enough to pay the fee"))?;
+
+        let cancel_tx = cancel_tx
+            .as_advanced_builder()
+            .set_outputs(vec![output.as_builder().capacity(capacity.pack()).build()])
+            .build();
+        let json_tx = ckb_jsonrpc_types::TransactionView::from(cancel_tx);
+
+        Ok(OnChainCancelRes {
+            transaction_id: transaction.transaction_id,
+            cancel_tx_hash: json_tx.hash.to_string(),
+            payload: serde_json::to_string_pretty(&json_tx).unwrap(),
+        })
+    }
+
+    /// Propose a signed cancellation from `build_onchain_cancellation`. It is stored as a
+    /// regular proposal linked to the original, whichever of the two commits first
+    /// supersedes the other.
+    pub async fn propose_onchain_cancellation(
+        &self,
+        signer_address: &str,
+        txid: &str,
+        req: OnChainCancelReq,
+    ) -> Result<NewTransferRes, AppError> {
+        let (transaction, multi_sig_info, tx) =
+            self.request_signed_proposal(signer_address, txid).await?;
+
+        let cancel_info: ckb_jsonrpc_types::TransactionView =
+            serde_json::from_str(req.payload.as_str()).map_err(|err| {
+                AppError::new(400)
+                    .cause(err)
+                    .message("invalid transaction json")
+            })?;
+        let cancel_tx = Transaction::from(cancel_info.inner).into_view();
+
+        // A cancellation spends exactly the inputs of the original and pays them back
+        let mut inputs: Vec<Vec<u8>> = tx
+            .input_pts_iter()
+            .map(|out_point| out_point.as_slice().to_vec())
+            .collect();
+        let mut cancel_inputs: Vec<Vec<u8>> = cancel_tx
+            .input_pts_iter()
+            .map(|out_point| out_point.as_slice().to_vec())
+            .collect();
+        inputs.sort();
+        cancel_inputs.sort();
+        let multi_sig_address = Address::from_str(&multi_sig_info.multi_sig_address)
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        let multi_sig_lock = Script::from(&multi_sig_address);
+        let pays_back = cancel_tx.outputs().into_iter().all(|output| {
+            output.lock().as_slice() == multi_sig_lock.as_slice() && output.type_().is_none()
+        });
+        if inputs != cancel_inputs || !pays_back {
+            return Err(
+                AppError::new(400).message("Payload is not a cancellation of this transaction")
+            );
+        }
+
+        let res = self
+            .create_new_transfer(
+                &signer_address.to_owned(),
+                NewTransferReq {
+                    signature: req.signature,
+                    payload: req.payload,
+                    expires_at: None,
+                    memo: None,
+                },
+            )
+            .await?;
+        let cancellation = self
+            .multi_sig_dao
+            .link_cancellation(&res.transaction.transaction_id, &transaction.transaction_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .unwrap_or(res.transaction);
+
+        Ok(NewTransferRes {
+            transaction: cancellation,
+            conflicts: res.conflicts,
+        })
+    }
+
+    /// A proposal of the signer which collected its signatures but is not on chain yet.
+    async fn request_signed_proposal(
+        &self,
+        signer_address: &str,
+        txid: &str,
+    ) -> Result<(CkbTransaction, MultiSigInfo, TransactionView), AppError> {
+        let transaction = self
+            .multi_sig_dao
+            .get_tx_by_hash_and_signer(signer_address, txid)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Transaction not found"))?;
+
+        if transaction.status.ne(&TRANSACTION_STATUS_PENDING)
+            && transaction.status.ne(&TRANSACTION_STATUS_READY_TO_EXECUTE)
+            && transaction.status.ne(&TRANSACTION_STATUS_FAILED)
+        {
+            return Err(AppError::new(400).message("Transaction not valid"));
+        }
+
+        let multi_sig_info = self
+            .request_multi_sig_info(&transaction.multi_sig_address)
+            .await?;
+        let signatures = self
+            .multi_sig_dao
+            .get_list_signatures_by_txid(&transaction.transaction_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        if signatures.len() < multi_sig_info.threshold as usize {
+            return Err(AppError::new(400)
+                .message("Transaction has not reached its threshold, cancel it instead"));
+        }
+
+        let tx_info: ckb_jsonrpc_types::TransactionView =
+            serde_json::from_str(transaction.payload.as_str()).map_err(|err| {
+                AppError::new(400)
+                    .cause(err)
+                    .message("invalid transaction json")
+            })?;
+        let tx = Transaction::from(tx_info.inner).into_view();
+
+        Ok((transaction, multi_sig_info, tx))
+    }
+
+    /// Export a proposal in ckb-cli's `tx` file format with a placeholder multisig lock,
+    /// along with the signatures collected so far.
+    pub async fn export_ckb_cli_tx(
+        &self,
+        user_address: &str,
+        txid: &str,
+    ) -> Result<CkbCliTx, AppError> {
+        let transaction = self
+            .multi_sig_dao
+            .get_tx_by_hash_and_signer(user_address, txid)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Transaction not found"))?;
+        let multi_sig_info = self
+            .request_multi_sig_info(&transaction.multi_sig_address)
+            .await?;
+
+        let tx_info: ckb_jsonrpc_types::TransactionView =
+            serde_json::from_str(transaction.payload.as_str()).map_err(|err| {
+                AppError::new(400)
+                    .cause(err)
+                    .message("invalid transaction json")
+            })?;
+        let tx = add_signature_to_witness(
+            multi_sig_info.threshold as usize,
+            &Transaction::from(tx_info.inner).into_view(),
+            &multi_sig_info.multi_sig_witness_data,
+            vec![],
+        )
+        .map_err(|err| AppError::new(400).cause(err).message("invalid witness"))?;
+
+        let multisig_config = parse_multisig_config(&multi_sig_info.multi_sig_witness_data)?;
+        let lock_args = Address::from_str(&multi_sig_info.multi_sig_address)
+            .map_err(|_| AppError::new(500).message("invalid multisig address"))?
+            .payload()
+            .args();
+        let sighash_addresses = multisig_config
+            .sighash_addresses()
+            .iter()
+            .map(|hash| {
+                Address::new(
+                    get_ckb_network(),
+                    AddressPayload::from_pubkey_hash(hash.clone()),
+                    true,
+                )
+                .to_string()
+            })
+            .collect();
+
+        let signatures: Vec<JsonBytes> = self
+            .multi_sig_dao
+            .get_list_signatures_by_txid(&transaction.transaction_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .iter()
+            .filter_map(|sig| hex::decode(&sig.signature).ok())
+            .map(JsonBytes::from_vec)
+            .collect();
+
+        let mut multisig_configs = HashMap::new();
+        multisig_configs.insert(
+            H160::from_slice(&lock_args).unwrap(),
+            CkbCliMultisigConfig {
+                sighash_addresses,
+                require_first_n: multisig_config.require_first_n(),
+                threshold: multisig_config.threshold(),
+            },
+        );
+        let mut signatures_by_args = HashMap::new();
+        if !signatures.is_empty() {
+            signatures_by_args.insert(JsonBytes::from_bytes(lock_args), signatures);
+        }
+
+        Ok(CkbCliTx {
+            transaction: tx.data().into(),
+            multisig_configs,
+            signatures: signatures_by_args,
+        })
+    }
+
+    /// Merge the signatures of a ckb-cli `tx` file into its proposal. Every signature is
+    /// verified against the account signers before any of them is stored.
+    pub async fn import_ckb_cli_tx(
+        &self,
+        user_address: &str,
+        req: CkbCliTx,
+    ) -> Result<ImportSignaturesRes, AppError> {
+        let tx_id = hex::encode(
+            Transaction::from(req.transaction)
+                .into_view()
+                .hash()
+                .raw_data(),
+        );
+        let transaction = self
+            .multi_sig_dao
+            .get_tx_by_hash_and_signer(user_address, &tx_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Transaction not found"))?;
+        let multi_sig_info = self
+            .request_multi_sig_info(&transaction.multi_sig_address)
+            .await?;
+
+        let lock_args = Address::from_str(&multi_sig_info.multi_sig_address)
+            .map_err(|_| AppError::new(500).message("invalid multisig address"))?
+            .payload()
+            .args();
+        let signatures = req
+            .signatures
+            .get(&JsonBytes::from_bytes(lock_args))
+            .cloned()
+            .unwrap_or_default();
+
+        let imported = self
+            .merge_signatures(
+                user_address,
+                &multi_sig_info,
+                &transaction,
+                signatures
+                    .iter()
+                    .map(|sig| sig.as_bytes().to_vec())
+                    .collect(),
+            )
+            .await?;
+
+        let status = self
+            .multi_sig_dao
+            .get_tx_by_hash(&transaction.transaction_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .map(|tx| tx.status)
+            .unwrap_or(transaction.status);
+
+        Ok(ImportSignaturesRes {
+            transaction_id: transaction.transaction_id,
+            imported,
+            status,
+        })
+    }
+
+    /// Export a proposal as a Neuron offline signature file. The collected signatures are
+    /// already filled into the witness lock.
+    pub async fn export_neuron_tx(
+        &self,
+        user_address: &str,
+        txid: &str,
+    ) -> Result<NeuronOfflineTx, AppError> {
+        let transaction = self
+            .multi_sig_dao
+            .get_tx_by_hash_and_signer(user_address, txid)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Transaction not found"))?;
+        let multi_sig_info = self
+            .request_multi_sig_info(&transaction.multi_sig_address)
+            .await?;
+
+        let tx_info: ckb_jsonrpc_types::TransactionView =
+            serde_json::from_str(transaction.payload.as_str()).map_err(|err| {
+                AppError::new(400)
+                    .cause(err)
+                    .message("invalid transaction json")
+            })?;
+        let ckb_signatures = self
+            .multi_sig_dao
+            .get_list_signatures_by_txid(&transaction.transaction_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        let tx = add_signature_to_witness(
+            multi_sig_info.threshold as usize,
+            &Transaction::from(tx_info.inner).into_view(),
+            &multi_sig_info.multi_sig_witness_data,
+            ckb_signatures
+                .iter()
+                .map(|s| Bytes::from(hex::decode(s.signature.clone()).unwrap()))
+                .collect(),
+        )
+        .map_err(|err| AppError::new(400).cause(err).message("invalid witness"))?;
+
+        // Neuron lists who signed by the blake160 of their public key
+        let multi_sig_lock = Script::from(
+            &Address::from_str(&multi_sig_info.multi_sig_address)
+                .map_err(|_| AppError::new(500).message("invalid multisig address"))?,
+        );
+        let signers: Vec<String> = ckb_signatures
+            .iter()
+            .filter_map(|s| Address::from_str(&s.signer_address).ok())
+            .map(|address| format!("0x{}", hex::encode(address.payload().args())))
+            .collect();
+        let mut signatures = HashMap::new();
+        signatures.insert(
+            format!(
+                "0x{}",
+                hex::encode(multi_sig_lock.calc_script_hash().raw_data())
+            ),
+            signers,
+        );
+
+        let status = if ckb_signatures.is_empty() {
+            "Unsigned"
+        } else if ckb_signatures.len() >= multi_sig_info.threshold as usize {
+            "Signed"
+        } else {
+            "PartiallySigned"
+        };
+
+        let inputs = resolve_inputs(&tx).await;
+        Ok(NeuronOfflineTx {
+            transaction: to_neuron_transaction(&tx, &inputs, signatures),
+            status: status.to_owned(),
+            tx_type: "Regular".to_owned(),
+            context: None,
+            description: None,
+        })
+    }
+
+    /// Merge the signatures found in the witness lock of a Neuron offline signature file
+    /// into its proposal.
+    pub async fn import_neuron_tx(
+        &self,
+        user_address: &str,
+        req: NeuronOfflineTx,
+    ) -> Result<ImportSignaturesRes, AppError> {
+        let tx = from_neuron_transaction(&req.transaction)?;
+        let tx_id = hex::encode(tx.hash().raw_data());
+        let transaction = self
+            .multi_sig_dao
+            .get_tx_by_hash_and_signer(user_address, &tx_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Transaction not found"))?;
+        let multi_sig_info = self
+            .request_multi_sig_info(&transaction.multi_sig_address)
+            .await?;
+
+        let signatures = extract_witness_signatures(&multi_sig_info, &tx)?;
+        let imported = self
+            .merge_signatures(user_address, &multi_sig_info, &transaction, signatures)
+            .await?;
+
+        let status = self
+            .multi_sig_dao
+            .get_tx_by_hash(&transaction.transaction_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .map(|tx| tx.status)
+            .unwrap_or(transaction.status);
+
+        Ok(ImportSignaturesRes {
+            transaction_id: transaction.transaction_id,
+            imported,
+            status,
+        })
+    }
+
+    /// Export a proposal as a CoBuild `BuildingPacket`. The collected signatures are filled
+    /// into the payload witness and the consumed cells are resolved from the chain.
+    pub async fn export_building_packet(
+        &self,
+        user_address: &str,
+        txid: &str,
+    ) -> Result<BuildingPacketRes, AppError> {
+        let transaction = self
+            .multi_sig_dao
+            .get_tx_by_hash_and_signer(user_address, txid)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Transaction not found"))?;
+        let multi_sig_info = self
+            .request_multi_sig_info(&transaction.multi_sig_address)
+            .await?;
+
+        let tx_info: ckb_jsonrpc_types::TransactionView =
+            serde_json::from_str(transaction.payload.as_str()).map_err(|err| {
+                AppError::new(400)
+                    .cause(err)
+                    .message("invalid transaction json")
+            })?;
+        let signatures = self
+            .multi_sig_dao
+            .get_list_signatures_by_txid(&transaction.transaction_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        let tx = add_signature_to_witness(
+            multi_sig_info.threshold as usize,
+            &Transaction::from(tx_info.inner).into_view(),
+            &multi_sig_info.multi_sig_witness_data,
+            signatures
+                .iter()
+                .map(|s| Bytes::from(hex::decode(s.signature.clone()).unwrap()))
+                .collect(),
+        )
+        .map_err(|err| AppError::new(400).cause(err).message("invalid witness"))?;
+
+        let message = cobuild_message(&tx)?.unwrap_or_else(empty_message);
+        let inputs = resolve_input_cells(&tx).await?;
+        let packet = BuildingPacket::new(message, tx.data(), inputs, None);
+
+        Ok(BuildingPacketRes {
+            transaction_id: transaction.transaction_id,
+            building_packet: JsonBytes::from_bytes(packet.as_bytes()),
+        })
+    }
+
+    /// Merge the signatures found in the payload of a CoBuild `BuildingPacket` into its
+    /// proposal. A packet without a proposal opens a new one, it must then carry the
+    /// signature of the importing member.
+    pub async fn import_building_packet(
+        &self,
+        user_address: &str,
+        req: BuildingPacketReq,
+    ) -> Result<ImportSignaturesRes, AppError> {
+        let packet = BuildingPacket::from_slice(req.building_packet.as_bytes())?;
+        let tx = packet.payload.clone().into_view();
+        if tx.inputs().len() != packet.resolved_inputs.len() {
+            return Err(AppError::new(400).message("Resolved inputs do not match the payload"));
+        }
+        if packet.has_actions() && cobuild_message(&tx)?.as_ref() != Some(&packet.message) {
+            return Err(AppError::new(400).message("Message does not match the payload"));
+        }
+
+        let tx_id = hex::encode(tx.hash().raw_data());
+        let transaction = match self
+            .multi_sig_dao
+            .get_tx_by_hash_and_signer(user_address, &tx_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+        {
+            Some(transaction) => transaction,
+            None => self.open_building_packet(user_address, &tx, req).await?,
+        };
+        let multi_sig_info = self
+            .request_multi_sig_info(&transaction.multi_sig_address)
+            .await?;
+
+        let signatures = extract_witness_signatures(&multi_sig_info, &tx)?;
+        let imported = self
+            .merge_signatures(user_address, &multi_sig_info, &transaction, signatures)
+            .await?;
+
+        let status = self
+            .multi_sig_dao
+            .get_tx_by_hash(&transaction.transaction_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .map(|tx| tx.status)
+            .unwrap_or(transaction.status);
+
+        Ok(ImportSignaturesRes {
+            transaction_id: transaction.transaction_id,
+            imported,
+            status,
+        })
+    }
+
+    async fn open_building_packet(
+        &self,
+        user_address: &str,
+        tx: &TransactionView,
+        req: BuildingPacketReq,
+    ) -> Result<CkbTransaction, AppError> {
+        let outpoints: Vec<ckb_jsonrpc_types::OutPoint> = tx
+            .input_pts_iter()
+            .map(ckb_jsonrpc_types::OutPoint::from)
+            .collect();
+        let multi_sig_address = self.validate_outpoints(&outpoints).await?;
+        let multi_sig_info = self.request_multi_sig_info(&multi_sig_address).await?;
+
+        let message = proposal_signing_message(&multi_sig_info, tx)?;
+        let mut signature = None;
+        for sig in extract_witness_signatures(&multi_sig_info, tx)? {
+            if match_signer(&message, &sig, &[user_address.to_owned()])?.is_some() {
+                signature = Some(sig);
+                break;
+            }
+        }
+        let signature = signature.ok_or(
+            AppError::new(400).message("Building packet must carry your signature to propose it"),
+        )?;
+
+        // Signatures are stored apart from the payload and filled in on broadcast
+        let payload = serde_json::to_string(&ckb_jsonrpc_types::TransactionView::from(
+            clear_witness_lock(tx)?,
+        ))
+        .map_err(|err| AppError::new(500).cause(err).message("invalid transaction"))?;
+        let res = self
+            .create_new_transfer(
+                &user_address.to_owned(),
+                NewTransferReq {
+                    signature: hex::encode(signature),
+                    payload,
+                    expires_at: req.expires_at,
+                    memo: req.memo,
+                },
+            )
+            .await?;
+        Ok(res.transaction)
+    }
+
+    /// Stream every proposal and incoming transfer of an account within `[from, to)` as CSV
+    /// or JSON. Rows are produced page by page so large accounts are never held in memory.
+    pub async fn export_transactions(
+        &self,
+        user_address: &str,
+        multisig_address: &str,
+        filters: TransactionExportFilters,
+    ) -> Result<(ExportFormat, impl Stream<Item = Result<Bytes, AppError>>), AppError> {
+        self.validate_signer(&user_address.to_owned(), &multisig_address.to_owned())
+            .await?;
+
+        let format = ExportFormat::parse(filters.format.as_deref())?;
+        let from = filters
+            .from
+            .map(|from| {
+                DateTime::from_timestamp(from, 0).ok_or(AppError::new(400).message("invalid from"))
+            })
+            .transpose()?;
+        let to = filters
+            .to
+            .map(|to| {
+                DateTime::from_timestamp(to, 0).ok_or(AppError::new(400).message("invalid to"))
+            })
+            .transpose()?;
+
+        let state = ExportState {
+            srv: self.clone(),
+            multi_sig_address: multisig_address.to_owned(),
+            from: from.map(|from| from.naive_utc()),
+            to: to.map(|to| to.naive_utc()),
+            writer: ExportWriter::new(format),
+            block_timestamps: HashMap::new(),
+            stage: ExportStage::Begin,
+        };
+
+        let rows = stream::unfold(state, |mut state| async move {
+            match state.next_chunk().await {
+                Ok(Some(chunk)) => Some((Ok(Bytes::from(chunk)), state)),
+                Ok(None) => None,
+                Err(err) => {
+                    state.stage = ExportStage::Done;
+                    Some((Err(err), state))
+                }
+            }
+        });
+        Ok((format, rows))
+    }
+
+    async fn proposal_export_row(
+        &self,
+        transaction: CkbTransaction,
+    ) -> Result<TransactionExportRow, AppError> {
+        let tx_info: ckb_jsonrpc_types::TransactionView =
+            serde_json::from_str(transaction.payload.as_str()).map_err(|err| {
+                AppError::new(400)
+                    .cause(err)
+                    .message("invalid transaction json")
+            })?;
+        let tx = Transaction::from(tx_info.inner).into_view();
+        let decoded = decode_transaction(&transaction.multi_sig_address, &tx).await;
+
+        let signers = self
+            .multi_sig_dao
+            .get_list_signatures_by_txid(&transaction.transaction_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .into_iter()
+            .map(|sig| sig.signer_address)
+            .collect();
+        let label = self
+            .multi_sig_dao
+            .get_label(&transaction.multi_sig_address, &transaction.transaction_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+
+        let sent: Vec<&DecodedOutput> = decoded
+            .outputs
+            .iter()
+            .filter(|output| !output.is_change)
+            .collect();
+        let mut counterparties: Vec<String> = vec![];
+        for output in sent.iter() {
+            if !counterparties.contains(&output.address) {
+                counterparties.push(output.address.clone());
+            }
+        }
+
+        Ok(TransactionExportRow {
+            direction: EXPORT_DIRECTION_OUTGOING.to_owned(),
+            transaction_id: transaction.transaction_id,
+            status: transaction_status_name(transaction.status).to_owned(),
+            created_at: transaction.created_at.and_utc().timestamp(),
+            broadcast_at: transaction
+                .broadcast_at
+                .map(|broadcast_at| broadcast_at.and_utc().timestamp()),
+            block_number: transaction.block_number,
+            amount: decoded.total_sent,
+            tokens: export_tokens(&sent),
+            fee: decoded.fee,
+            counterparties,
+            signers,
+            memo: transaction.memo,
+            category: label.as_ref().and_then(|label| label.category.clone()),
+            labels: label.map(|label| label.labels).unwrap_or_default(),
+        })
+    }
+
+    /// One page of the transfers received by the account, oldest first. Returns the rows
+    /// within the time range and the cursor of the next page, if any.
+    async fn incoming_export_rows(
+        &self,
+        multisig_address: &str,
+        from: Option<i64>,
+        to: Option<i64>,
+        cursor: Option<JsonBytes>,
+        block_timestamps: &mut HashMap<u64, i64>,
+    ) -> Result<(Vec<TransactionExportRow>, Option<JsonBytes>), AppError> {
+        let lock = Script::from(
+            &Address::from_str(multisig_address)
+                .map_err(|_| AppError::new(400).message("invalid multisig address"))?,
+        );
+        let page =
+            get_transactions_by_lock(lock.into(), Order::Asc, EXPORT_PAGE_SIZE as u32, cursor)
+                .await
+                .map_err(|err| {
+                    AppError::new(500)
+                        .cause(err)
+                        .message("get transactions failed")
+                })?;
+        let mut next_cursor =
+            (page.objects.len() as i64 >= EXPORT_PAGE_SIZE).then_some(page.last_cursor);
+
+        // A whole page before the range is skipped with a single header lookup
+        if let (Some(from), Some(last)) = (from, page.objects.last()) {
+            let block_number = match last {
+                Tx::Ungrouped(tx) => tx.block_number.into(),
+                Tx::Grouped(tx) => tx.block_number.into(),
+            };
+            if block_timestamp(block_number, block_timestamps).await? < from {
+                return Ok((vec![], next_cursor));
+            }
+        }
+
+        let mut rows = vec![];
+        for tx in page.objects {
+            let tx = match tx {
+                Tx::Grouped(tx) => tx,
+                Tx::Ungrouped(_) => continue,
+            };
+            // Transactions spending the account's cells are its own proposals
+            if tx
+                .cells
+                .iter()
+                .any(|(cell_type, _)| matches!(cell_type, CellType::Input))
+            {
+                continue;
+            }
+
+            let block_number: u64 = tx.block_number.into();
+            let timestamp = block_timestamp(block_number, block_timestamps).await?;
+            if from.is_some_and(|from| timestamp < from) {
+                continue;
+            }
+            if to.is_some_and(|to| timestamp >= to) {
+                next_cursor = None;
+                break;
+            }
+
+            rows.push(
+                self.incoming_export_row(multisig_address, tx.tx_hash, block_number, timestamp)
+                    .await?,
+            );
+        }
+
+        Ok((rows, next_cursor))
+    }
+
+    async fn incoming_export_row(
+        &self,
+        multisig_address: &str,
+        tx_hash: H256,
+        block_number: u64,
+        timestamp: i64,
+    ) -> Result<TransactionExportRow, AppError> {
+        let tx = get_transaction_body(tx_hash.clone())
+            .await
+            .map_err(|err| {
+                AppError::new(500)
+                    .cause(err)
+                    .message("get transaction failed")
+            })?
+            .ok_or(AppError::new(500).message("transaction not found"))?;
+        let tx = Transaction::from(tx).into_view();
+        let decoded = decode_transaction(multisig_address, &tx).await;
+
+        let tx_id = hex::encode(tx_hash.as_bytes());
+        let label = self
+            .multi_sig_dao
+            .get_label(&multisig_address.to_owned(), &tx_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+
+        let received: Vec<&DecodedOutput> = decoded
+            .outputs
+            .iter()
+            .filter(|output| output.is_change)
+            .collect();
+        let mut counterparties: Vec<String> = vec![];
+        for address in decoded
+            .inputs
+            .iter()
+            .filter_map(|input| input.address.clone())
+        {
+            if address.ne(multisig_address) && !counterparties.contains(&address) {
+                counterparties.push(address);
             }
-            None => Err(AppError::new(404).message("Transaction not found")),
         }
+
+        Ok(TransactionExportRow {
+            direction: EXPORT_DIRECTION_INCOMING.to_owned(),
+            transaction_id: tx_id,
+            status: transaction_status_name(TRANSACTION_STATUS_COMMITED).to_owned(),
+            created_at: timestamp,
+            broadcast_at: None,
+            block_number: Some(block_number as i64),
+            amount: received.iter().map(|output| output.capacity).sum(),
+            tokens: export_tokens(&received),
+            fee: decoded.fee,
+            counterparties,
+            signers: vec![],
+            memo: None,
+            category: label.as_ref().and_then(|label| label.category.clone()),
+            labels: label.map(|label| label.labels).unwrap_or_default(),
+        })
+    }
+
+    /// Bundle a proposal with its collected signatures in the witness, the multisig config
+    /// and who still has to sign, so it can be finished and broadcast without this server.
+    pub async fn request_transaction_bundle(
+        &self,
+        user_address: &str,
+        txid: &str,
+    ) -> Result<TransactionBundle, AppError> {
+        let transaction = self
+            .multi_sig_dao
+            .get_tx_by_hash_and_signer(user_address, txid)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Transaction not found"))?;
+        let multi_sig_info = self
+            .request_multi_sig_info(&transaction.multi_sig_address)
+            .await?;
+
+        let tx_info: ckb_jsonrpc_types::TransactionView =
+            serde_json::from_str(transaction.payload.as_str()).map_err(|err| {
+                AppError::new(400)
+                    .cause(err)
+                    .message("invalid transaction json")
+            })?;
+        let ckb_signatures = self
+            .multi_sig_dao
+            .get_list_signatures_by_txid(&transaction.transaction_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        let tx = add_signature_to_witness(
+            multi_sig_info.threshold as usize,
+            &Transaction::from(tx_info.inner).into_view(),
+            &multi_sig_info.multi_sig_witness_data,
+            ckb_signatures
+                .iter()
+                .map(|s| Bytes::from(hex::decode(s.signature.clone()).unwrap()))
+                .collect(),
+        )
+        .map_err(|err| AppError::new(400).cause(err).message("invalid witness"))?;
+        let signing_message = proposal_signing_message(&multi_sig_info, &tx)?;
+
+        let multisig_config = parse_multisig_config(&multi_sig_info.multi_sig_witness_data)?;
+        let sighash_addresses = multisig_config
+            .sighash_addresses()
+            .iter()
+            .map(|hash| {
+                Address::new(
+                    get_ckb_network(),
+                    AddressPayload::from_pubkey_hash(hash.clone()),
+                    true,
+                )
+                .to_string()
+            })
+            .collect();
+
+        let signers: Vec<String> = ckb_signatures
+            .iter()
+            .map(|sig| sig.signer_address.clone())
+            .collect();
+        let missing_signers = self
+            .multi_sig_dao
+            .request_list_signers(&multi_sig_info.multi_sig_address, &user_address.to_owned())
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .into_iter()
+            .map(|signer| signer.signer_address)
+            .filter(|signer_address| !signers.contains(signer_address))
+            .collect();
+
+        Ok(TransactionBundle {
+            transaction_id: transaction.transaction_id,
+            multi_sig_address: multi_sig_info.multi_sig_address.clone(),
+            status: transaction.status,
+            transaction: tx.data().into(),
+            signing_message: format!("0x{}", hex::encode(signing_message)),
+            multisig_config: BundleMultisigConfig {
+                witness_data: format!("0x{}", multi_sig_info.multi_sig_witness_data),
+                sighash_addresses,
+                require_first_n: multisig_config.require_first_n(),
+                threshold: multisig_config.threshold(),
+            },
+            missing_signatures: (multi_sig_info.threshold as usize).saturating_sub(signers.len()),
+            signers,
+            missing_signers,
+        })
+    }
+
+    /// Encode the signing request of a proposal as UR parts for air-gapped signers, see
+    /// `services::ur` for the CBOR schema.
+    pub async fn request_sign_request_ur(
+        &self,
+        user_address: &str,
+        txid: &str,
+        fragment_size: usize,
+    ) -> Result<UrSignRequestRes, AppError> {
+        let transaction = self
+            .multi_sig_dao
+            .get_tx_by_hash_and_signer(user_address, txid)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Transaction not found"))?;
+        if transaction.status.ne(&TRANSACTION_STATUS_PENDING) {
+            return Err(AppError::new(400).message("Transaction not valid"));
+        }
+        let multi_sig_info = self
+            .request_multi_sig_info(&transaction.multi_sig_address)
+            .await?;
+
+        let tx_info: ckb_jsonrpc_types::TransactionView =
+            serde_json::from_str(transaction.payload.as_str()).map_err(|err| {
+                AppError::new(400)
+                    .cause(err)
+                    .message("invalid transaction json")
+            })?;
+        let tx = add_signature_to_witness(
+            multi_sig_info.threshold as usize,
+            &Transaction::from(tx_info.inner).into_view(),
+            &multi_sig_info.multi_sig_witness_data,
+            vec![],
+        )
+        .map_err(|err| AppError::new(400).cause(err).message("invalid witness"))?;
+        let message = proposal_signing_message(&multi_sig_info, &tx)?;
+        let config_data = hex::decode(&multi_sig_info.multi_sig_witness_data).map_err(|err| {
+            AppError::new(500)
+                .cause(err)
+                .message("invalid multisig config")
+        })?;
+
+        let request = Cbor::Map(vec![
+            (
+                Cbor::Unsigned(1),
+                Cbor::Bytes(tx.hash().raw_data().to_vec()),
+            ),
+            (
+                Cbor::Unsigned(2),
+                Cbor::Bytes(tx.data().as_slice().to_vec()),
+            ),
+            (Cbor::Unsigned(3), Cbor::Bytes(message.to_vec())),
+            (
+                Cbor::Unsigned(4),
+                Cbor::Text(multi_sig_info.multi_sig_address.clone()),
+            ),
+            (Cbor::Unsigned(5), Cbor::Bytes(config_data)),
+            (Cbor::Unsigned(6), Cbor::Text(UR_ORIGIN.to_owned())),
+        ]);
+
+        Ok(UrSignRequestRes {
+            transaction_id: transaction.transaction_id,
+            ur_type: UR_TYPE_SIGN_REQUEST.to_owned(),
+            parts: encode_ur(UR_TYPE_SIGN_REQUEST, &request.encode(), fragment_size),
+        })
+    }
+
+    /// Everything the Ledger CKB app needs to display and sign a proposal: the previous
+    /// transactions of the inputs, fetched from the node, and the sign / change paths of
+    /// the device.
+    pub async fn request_ledger_signing_context(
+        &self,
+        user_address: &str,
+        txid: &str,
+        filters: LedgerContextFilters,
+    ) -> Result<LedgerSigningContext, AppError> {
+        let sign_path = filters.sign_path.unwrap_or(DEFAULT_SIGN_PATH.to_owned());
+        let change_path = filters
+            .change_path
+            .unwrap_or(DEFAULT_CHANGE_PATH.to_owned());
+        let sign_path_indexes = parse_bip32_path(&sign_path)?;
+        let change_path_indexes = parse_bip32_path(&change_path)?;
+
+        let transaction = self
+            .multi_sig_dao
+            .get_tx_by_hash_and_signer(user_address, txid)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Transaction not found"))?;
+        if transaction.status.ne(&TRANSACTION_STATUS_PENDING) {
+            return Err(AppError::new(400).message("Transaction not valid"));
+        }
+        let multi_sig_info = self
+            .request_multi_sig_info(&transaction.multi_sig_address)
+            .await?;
+
+        let tx_info: ckb_jsonrpc_types::TransactionView =
+            serde_json::from_str(transaction.payload.as_str()).map_err(|err| {
+                AppError::new(400)
+                    .cause(err)
+                    .message("invalid transaction json")
+            })?;
+        let tx = add_signature_to_witness(
+            multi_sig_info.threshold as usize,
+            &Transaction::from(tx_info.inner).into_view(),
+            &multi_sig_info.multi_sig_witness_data,
+            vec![],
+        )
+        .map_err(|err| AppError::new(400).cause(err).message("invalid witness"))?;
+        let signing_message = proposal_signing_message(&multi_sig_info, &tx)?;
+        let input_txs = resolve_input_transactions(&tx).await?;
+
+        Ok(LedgerSigningContext {
+            transaction_id: transaction.transaction_id,
+            multi_sig_address: multi_sig_info.multi_sig_address.clone(),
+            annotated_transaction: format!(
+                "0x{}",
+                hex::encode(annotated_transaction(
+                    &sign_path_indexes,
+                    &change_path_indexes,
+                    &tx,
+                    &input_txs,
+                ))
+            ),
+            sign_path,
+            change_path,
+            transaction: tx.data().into(),
+            input_transactions: input_txs.into_iter().map(Into::into).collect(),
+            signing_message: format!("0x{}", hex::encode(signing_message)),
+            multisig_config: format!("0x{}", multi_sig_info.multi_sig_witness_data),
+        })
+    }
+
+    /// Reassemble a UR encoded `ckb-signature` and submit it. The signature must be the
+    /// member's own signature of the proposal's signing message.
+    pub async fn submit_signature_ur(
+        &self,
+        user_address: &str,
+        parts: &[String],
+    ) -> Result<CkbTransaction, AppError> {
+        let (ur_type, message) = decode_ur(parts)?;
+        if ur_type.ne(UR_TYPE_SIGNATURE) {
+            return Err(AppError::new(400).message(&format!("Expected a {} UR", UR_TYPE_SIGNATURE)));
+        }
+        let response = Cbor::decode(&message)?;
+        let request_id = response
+            .get(1)
+            .and_then(|value| value.as_bytes())
+            .filter(|value| value.len() == 32)
+            .ok_or(AppError::new(400).message("invalid request id"))?;
+        let signature = response
+            .get(2)
+            .and_then(|value| value.as_bytes())
+            .ok_or(AppError::new(400).message("Signature Invalid"))?;
+
+        let txid = hex::encode(request_id);
+        let transaction = self
+            .multi_sig_dao
+            .get_tx_by_hash_and_signer(user_address, &txid)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Transaction not found"))?;
+        let multi_sig_info = self
+            .request_multi_sig_info(&transaction.multi_sig_address)
+            .await?;
+
+        let tx_info: ckb_jsonrpc_types::TransactionView =
+            serde_json::from_str(transaction.payload.as_str()).map_err(|err| {
+                AppError::new(400)
+                    .cause(err)
+                    .message("invalid transaction json")
+            })?;
+        let tx = Transaction::from(tx_info.inner).into_view();
+        let signing_message = proposal_signing_message(&multi_sig_info, &tx)?;
+        if match_signer(&signing_message, signature, &[user_address.to_owned()])?.is_none() {
+            return Err(AppError::new(400).message("Signature does not belong to you"));
+        }
+
+        self.submit_signature(&user_address.to_owned(), &hex::encode(signature), &txid)
+            .await
+    }
+
+    /// Verify signatures produced outside of the app and submit the new ones through
+    /// `submit_signature`. Returns the addresses of the signers whose signature was added.
+    async fn merge_signatures(
+        &self,
+        user_address: &str,
+        multi_sig_info: &MultiSigInfo,
+        transaction: &CkbTransaction,
+        signatures: Vec<Vec<u8>>,
+    ) -> Result<Vec<String>, AppError> {
+        if signatures.is_empty() {
+            return Err(AppError::new(400).message("No signatures for this multisig account"));
+        }
+
+        let tx_info: ckb_jsonrpc_types::TransactionView =
+            serde_json::from_str(transaction.payload.as_str()).map_err(|err| {
+                AppError::new(400)
+                    .cause(err)
+                    .message("invalid transaction json")
+            })?;
+        let tx = Transaction::from(tx_info.inner).into_view();
+        let message = proposal_signing_message(multi_sig_info, &tx)?;
+
+        let signer_addresses: Vec<String> = self
+            .multi_sig_dao
+            .request_list_signers(&multi_sig_info.multi_sig_address, &user_address.to_owned())
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .iter()
+            .map(|signer| signer.signer_address.clone())
+            .collect();
+        let signed: Vec<String> = self
+            .multi_sig_dao
+            .get_list_signatures_by_txid(&transaction.transaction_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .iter()
+            .map(|sig| sig.signer_address.clone())
+            .collect();
+
+        let mut new_signatures: Vec<(String, String)> = vec![];
+        for signature in signatures {
+            let signer_address = match_signer(&message, &signature, &signer_addresses)?.ok_or(
+                AppError::new(400).message("Signature does not belong to a signer of this account"),
+            )?;
+            if signed.contains(&signer_address)
+                || new_signatures
+                    .iter()
+                    .any(|(address, _)| address.eq(&signer_address))
+            {
+                continue;
+            }
+            new_signatures.push((signer_address, hex::encode(signature)));
+        }
+
+        let missing = (multi_sig_info.threshold as usize).saturating_sub(signed.len());
+        let mut imported = vec![];
+        for (signer_address, signature) in new_signatures.into_iter().take(missing) {
+            self.submit_signature(&signer_address, &signature, &transaction.transaction_id)
+                .await?;
+            imported.push(signer_address);
+        }
+
+        Ok(imported)
+    }
+
+    pub async fn request_list_labels(
+        &self,
+        user_address: &str,
+        multisig_address: &str,
+    ) -> Result<Vec<TransactionLabel>, AppError> {
+        self.validate_signer(&user_address.to_owned(), &multisig_address.to_owned())
+            .await?;
+
+        self.multi_sig_dao
+            .get_labels_by_multisig(&multisig_address.to_owned())
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))
+    }
+
+    /// Set the labels and category of a proposal or of an incoming transfer of the account.
+    /// Labels are lowercased and deduplicated.
+    pub async fn update_labels(
+        &self,
+        user_address: &str,
+        multisig_address: &str,
+        tx_hash: &str,
+        req: TransactionLabelReq,
+    ) -> Result<TransactionLabel, AppError> {
+        self.validate_signer(&user_address.to_owned(), &multisig_address.to_owned())
+            .await?;
+
+        let tx_hash = tx_hash.trim_start_matches("0x").to_lowercase();
+        if H256::from_str(&tx_hash).is_err() {
+            return Err(AppError::new(400).message("invalid tx hash"));
+        }
+
+        let mut labels: Vec<String> = vec![];
+        for label in req.labels {
+            let label = label.trim().to_lowercase();
+            if label.is_empty() || labels.contains(&label) {
+                continue;
+            }
+            if label.chars().count() > MAX_LABEL_LENGTH {
+                return Err(AppError::new(400).message(&format!(
+                    "Label must not exceed {} characters",
+                    MAX_LABEL_LENGTH
+                )));
+            }
+            labels.push(label);
+        }
+        if labels.len() > MAX_LABELS {
+            return Err(
+                AppError::new(400).message(&format!("At most {} labels are allowed", MAX_LABELS))
+            );
+        }
+
+        let category = req
+            .category
+            .map(|category| category.trim().to_lowercase())
+            .filter(|category| !category.is_empty());
+        if category
+            .as_ref()
+            .is_some_and(|category| category.chars().count() > MAX_LABEL_LENGTH)
+        {
+            return Err(AppError::new(400).message(&format!(
+                "Category must not exceed {} characters",
+                MAX_LABEL_LENGTH
+            )));
+        }
+
+        self.multi_sig_dao
+            .upsert_label(&multisig_address.to_owned(), &tx_hash, &labels, &category)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))
     }
 
     pub async fn get_invites_list(&self, address: &String) -> Result<Vec<InviteInfo>, AppError> {
@@ -758,6 +2878,14 @@ impl MultiSigSrv {
 
                 if is_ok {
                     transaction.commit().await.unwrap();
+                    let event = if req.status == MultiSigInviteStatus::ACCEPTED as i16 {
+                        EVENT_INVITE_ACCEPTED
+                    } else {
+                        EVENT_INVITE_REJECTED
+                    };
+                    self.event_dispatcher
+                        .publish(event, &req.multisig_address, None, Some(&req.address))
+                        .await;
                     return Ok(true);
                 }
 
@@ -776,6 +2904,17 @@ impl MultiSigSrv {
         signer_address: &str,
         transacion_id: &str,
         errors: &str,
+    ) {
+        self.save_categorized_error(signer_address, transacion_id, None, errors)
+            .await;
+    }
+
+    async fn save_categorized_error(
+        &self,
+        signer_address: &str,
+        transacion_id: &str,
+        category: Option<&str>,
+        errors: &str,
     ) {
         let _ = self
             .multi_sig_dao
@@ -788,8 +2927,24 @@ impl MultiSigSrv {
                 &signer_address.to_string(),
                 &transacion_id.to_string(),
                 &errors.to_string(),
+                &category.map(|category| category.to_owned()),
             )
             .await;
+
+        if let Ok(Some(transaction)) = self
+            .multi_sig_dao
+            .get_tx_by_hash(&transacion_id.to_string())
+            .await
+        {
+            self.event_dispatcher
+                .publish(
+                    EVENT_TRANSACTION_FAILED,
+                    &transaction.multi_sig_address,
+                    Some(transacion_id),
+                    None,
+                )
+                .await;
+        }
     }
 
     pub async fn rp_transaction_summary(
@@ -817,41 +2972,367 @@ impl MultiSigSrv {
                         .message("invalid transaction json")
                 })?;
             let tx_view = Transaction::from(tx_info.clone().inner).into_view();
-            let first_output: ckb_types::packed::CellOutput = tx_view.outputs().get(0).unwrap();
-            result.total_amount_pending += &first_output.capacity().unpack();
+            result.total_amount_pending += decode_outputs(multisig_address, &tx_view)
+                .iter()
+                .filter(|output| !output.is_change)
+                .map(|output| output.capacity)
+                .sum::<u64>();
         }
 
         Ok(result)
     }
 
-    pub async fn update_transaction_commited(
+    /// Sync a broadcast transaction with the chain. Returns the status the transaction ends up in.
+    pub async fn sync_transaction_status(
+        &self,
+        transaction: &CkbTransaction,
+    ) -> Result<i16, AppError> {
+        let tx_hash = H256::from_str(&transaction.transaction_id)
+            .map_err(|err| AppError::new(400).cause(err).message("invalid tx hash"))?;
+        let tx_with_status = get_transaction(tx_hash).await.map_err(|err| {
+            AppError::new(500)
+                .cause(err)
+                .message("get transaction failed")
+        })?;
+
+        let tx_status = match tx_with_status {
+            Some(tx_with_status) => tx_with_status.tx_status,
+            None => return Ok(transaction.status),
+        };
+
+        match tx_status.status {
+            Status::Committed => {
+                let block_number: u64 = tx_status.block_number.unwrap_or_default().into();
+                let block_hash = tx_status
+                    .block_hash
+                    .map(|hash| hash.to_string())
+                    .unwrap_or_default();
+                let tip_block_number = get_tip_block_number().await.map_err(|err| {
+                    AppError::new(500)
+                        .cause(err)
+                        .message("get tip block number failed")
+                })?;
+                let confirmations = tip_block_number.saturating_sub(block_number) + 1;
+
+                let confirmation_threshold: u64 = config::get("confirmation_threshold");
+                let status = if confirmations >= confirmation_threshold {
+                    TRANSACTION_STATUS_COMMITED
+                } else {
+                    TRANSACTION_STATUS_IN_PROGRESSING
+                };
+
+                // Inputs are spent as soon as the transaction is in a block, the other
+                // proposals on them can never be broadcast. Superseding is idempotent, so it
+                // doesn't matter that this runs again until the confirmations are reached.
+                self.multi_sig_dao
+                    .supersede_conflicting_txs(
+                        &transaction.transaction_id,
+                        &UNSENT_TRANSACTION_STATUSES,
+                    )
+                    .await
+                    .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+
+                self.multi_sig_dao
+                    .update_transaction_block(
+                        &transaction.transaction_id,
+                        status,
+                        block_number as i64,
+                        &block_hash,
+                        confirmations as i64,
+                    )
+                    .await
+                    .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+
+                if status.eq(&TRANSACTION_STATUS_COMMITED) {
+                    self.event_dispatcher
+                        .publish(
+                            EVENT_TRANSACTION_COMMITTED,
+                            &transaction.multi_sig_address,
+                            Some(&transaction.transaction_id),
+                            None,
+                        )
+                        .await;
+                }
+                Ok(status)
+            }
+            Status::Rejected => {
+                let reason = tx_status.reason.unwrap_or("rejected by node".to_owned());
+                self.save_categorized_error(
+                    "chainStatus",
+                    &transaction.transaction_id,
+                    Some(TRANSACTION_ERROR_NODE_REJECTED),
+                    &reason,
+                )
+                .await;
+                Ok(TRANSACTION_STATUS_FAILED)
+            }
+            Status::Unknown => {
+                // The node has forgotten the transaction, it was dropped from the pool
+                let timeout_secs: i64 = config::get("unknown_tx_timeout_secs");
+                let dropped = transaction.broadcast_at.is_some_and(|broadcast_at| {
+                    Utc::now().naive_utc() - broadcast_at > Duration::seconds(timeout_secs)
+                });
+                if transaction.status.eq(&TRANSACTION_STATUS_IN_PROGRESSING) && dropped {
+                    self.save_transaction_error(
+                        "chainStatus",
+                        &transaction.transaction_id,
+                        "transaction dropped from pool",
+                    )
+                    .await;
+                    return Ok(TRANSACTION_STATUS_FAILED);
+                }
+                Ok(transaction.status)
+            }
+            Status::Pending | Status::Proposed => Ok(transaction.status),
+        }
+    }
+
+    pub async fn sync_in_progress_transactions(&self) -> Result<(), AppError> {
+        let transactions = self
+            .multi_sig_dao
+            .get_txs_by_status(TRANSACTION_STATUS_IN_PROGRESSING)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+
+        for transaction in transactions {
+            if let Err(err) = self.sync_transaction_status(&transaction).await {
+                log::warn!(
+                    "sync transaction {} failed: {}",
+                    transaction.transaction_id,
+                    err
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn recheck_transactions(
         &self,
+        user_address: &str,
+        multisig_address: &str,
         req: &UpdateTransactionStatusReq,
     ) -> Result<UpdateTransactionStatusRes, AppError> {
         let mut results: HashMap<String, bool> = HashMap::new();
 
         for tx_hash in req.tx_hashes.iter() {
-            if let Some(transaction) = self
+            let transaction = self
                 .multi_sig_dao
-                .get_tx_by_hash(tx_hash)
+                .get_tx_by_hash_and_signer(user_address, tx_hash)
                 .await
-                .ok()
-                .flatten()
+                .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+
+            let transaction = match transaction {
+                Some(transaction) if transaction.multi_sig_address.eq(multisig_address) => {
+                    transaction
+                }
+                _ => {
+                    results.insert(tx_hash.clone(), false);
+                    continue;
+                }
+            };
+
+            let status = if transaction.status.eq(&TRANSACTION_STATUS_PENDING)
+                || transaction.status.eq(&TRANSACTION_STATUS_IN_PROGRESSING)
             {
-                if transaction.status.eq(&TRANSACTION_STATUS_PENDING)
-                    || transaction.status.eq(&TRANSACTION_STATUS_IN_PROGRESSING)
-                {
-                    if let Ok(true) = self
+                self.sync_transaction_status(&transaction).await?
+            } else {
+                transaction.status
+            };
+            results.insert(tx_hash.clone(), status.eq(&TRANSACTION_STATUS_COMMITED));
+        }
+
+        Ok(UpdateTransactionStatusRes { results })
+    }
+}
+
+/// Verdict of a node check on an unsigned transaction, `None` when the node accepted it.
+/// Only the multisig lock group rejecting the placeholder witness is expected, any other
+/// failure means the proposal can never be mined.
+async fn unsigned_preflight_verdict(
+    tx: &TransactionView,
+    multi_sig_lock_hash: &ckb_types::packed::Byte32,
+    result: Result<(), ckb_sdk::RpcError>,
+) -> Option<(&'static str, Option<String>)> {
+    let err = match result {
+        Ok(_) => return None,
+        Err(ckb_sdk::RpcError::Rpc(err)) => err,
+        Err(err) => return Some((PREFLIGHT_STATUS_UNAVAILABLE, Some(err.to_string()))),
+    };
+    if err.code.code() != RPC_TRANSACTION_FAILED_TO_VERIFY {
+        return Some((PREFLIGHT_STATUS_FAILED, Some(err.message)));
+    }
+
+    let input = match failing_lock_input(&err.message).and_then(|index| tx.inputs().get(index)) {
+        Some(input) => input,
+        None => return Some((PREFLIGHT_STATUS_FAILED, Some(err.message))),
+    };
+    let out_point = input.previous_output();
+    let index: u32 = out_point.index().unpack();
+    let lock = match get_transaction_body(out_point.tx_hash().unpack()).await {
+        Ok(previous_tx) => previous_tx
+            .and_then(|previous_tx| previous_tx.outputs.get(index as usize).cloned())
+            .map(|output| Script::from(output.lock)),
+        Err(err) => return Some((PREFLIGHT_STATUS_UNAVAILABLE, Some(err.to_string()))),
+    };
+    if lock.is_some_and(|lock| lock.calc_script_hash().as_slice() == multi_sig_lock_hash.as_slice())
+    {
+        return Some((
+            PREFLIGHT_STATUS_PENDING_SIGNATURES,
+            Some("The multisig lock is verified once the threshold is reached".to_owned()),
+        ));
+    }
+    Some((PREFLIGHT_STATUS_FAILED, Some(err.message)))
+}
+
+/// Input index of the failing lock group. The node names a script group by its first
+/// input, as in `TransactionScriptError { source: Inputs[0].Lock, cause: .. }`.
+fn failing_lock_input(message: &str) -> Option<usize> {
+    let (_, source) = message.split_once("source: Inputs[")?;
+    let (index, rest) = source.split_once(']')?;
+    if !rest.starts_with(".Lock") {
+        return None;
+    }
+    index.parse().ok()
+}
+
+/// Drop the multisig lock of the first witness.
+fn clear_witness_lock(tx: &TransactionView) -> Result<TransactionView, AppError> {
+    let mut witnesses: Vec<ckb_types::packed::Bytes> = tx.witnesses().into_iter().collect();
+    let witness = match witnesses.first() {
+        Some(witness) => witness.raw_data(),
+        None => return Ok(tx.clone()),
+    };
+    if witness.is_empty() {
+        return Ok(tx.clone());
+    }
+
+    ensure_witness_args(tx)?;
+    witnesses[0] = WitnessArgs::from_slice(&witness)
+        .map_err(|err| AppError::new(400).cause(err).message("invalid witness"))?
+        .as_builder()
+        .lock(None::<Bytes>.pack())
+        .build()
+        .as_bytes()
+        .pack();
+    Ok(tx.as_advanced_builder().set_witnesses(witnesses).build())
+}
+
+enum ExportStage {
+    Begin,
+    Proposals(i64),
+    Incoming(Option<JsonBytes>),
+    End,
+    Done,
+}
+
+struct ExportState {
+    srv: MultiSigSrv,
+    multi_sig_address: String,
+    from: Option<NaiveDateTime>,
+    to: Option<NaiveDateTime>,
+    writer: ExportWriter,
+    block_timestamps: HashMap<u64, i64>,
+    stage: ExportStage,
+}
+
+impl ExportState {
+    /// The next non-empty chunk of the export, `None` once it is complete.
+    async fn next_chunk(&mut self) -> Result<Option<String>, AppError> {
+        loop {
+            match &self.stage {
+                ExportStage::Begin => {
+                    self.stage = ExportStage::Proposals(0);
+                    return Ok(Some(self.writer.begin()));
+                }
+                ExportStage::Proposals(offset) => {
+                    let offset = *offset;
+                    let transactions = self
+                        .srv
                         .multi_sig_dao
-                        .update_transaction_status(tx_hash, TRANSACTION_STATUS_COMMITED)
+                        .get_transactions_for_export(
+                            &self.multi_sig_address,
+                            &self.from,
+                            &self.to,
+                            offset,
+                            EXPORT_PAGE_SIZE,
+                        )
                         .await
-                    {
-                        results.insert(tx_hash.clone(), true);
+                        .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+                    self.stage = if (transactions.len() as i64) < EXPORT_PAGE_SIZE {
+                        ExportStage::Incoming(None)
+                    } else {
+                        ExportStage::Proposals(offset + EXPORT_PAGE_SIZE)
+                    };
+
+                    let mut rows = vec![];
+                    for transaction in transactions {
+                        rows.push(self.srv.proposal_export_row(transaction).await?);
+                    }
+                    if !rows.is_empty() {
+                        return Ok(Some(self.writer.rows(&rows)));
+                    }
+                }
+                ExportStage::Incoming(cursor) => {
+                    let (rows, next_cursor) = self
+                        .srv
+                        .incoming_export_rows(
+                            &self.multi_sig_address,
+                            self.from.map(|from| from.and_utc().timestamp()),
+                            self.to.map(|to| to.and_utc().timestamp()),
+                            cursor.clone(),
+                            &mut self.block_timestamps,
+                        )
+                        .await?;
+                    self.stage = match next_cursor {
+                        Some(next_cursor) => ExportStage::Incoming(Some(next_cursor)),
+                        None => ExportStage::End,
+                    };
+                    if !rows.is_empty() {
+                        return Ok(Some(self.writer.rows(&rows)));
+                    }
+                }
+                ExportStage::End => {
+                    self.stage = ExportStage::Done;
+                    let end = self.writer.end();
+                    if !end.is_empty() {
+                        return Ok(Some(end));
                     }
                 }
+                ExportStage::Done => return Ok(None),
             }
         }
+    }
+}
 
-        Ok(UpdateTransactionStatusRes { results })
+/// Block timestamp in seconds, cached across the pages of an export.
+async fn block_timestamp(
+    block_number: u64,
+    block_timestamps: &mut HashMap<u64, i64>,
+) -> Result<i64, AppError> {
+    if let Some(timestamp) = block_timestamps.get(&block_number) {
+        return Ok(*timestamp);
     }
+
+    let timestamp = get_block_timestamp(block_number)
+        .await
+        .map_err(|err| AppError::new(500).cause(err).message("get header failed"))?
+        .ok_or(AppError::new(500).message("block not found"))?;
+    let timestamp = (timestamp / 1000) as i64;
+    block_timestamps.insert(block_number, timestamp);
+    Ok(timestamp)
+}
+
+fn export_tokens(outputs: &[&DecodedOutput]) -> Vec<String> {
+    outputs
+        .iter()
+        .filter(|output| output.asset.asset_type.ne(&AssetType::Ckb))
+        .map(|output| {
+            format!(
+                "{}:{}",
+                output.asset.type_hash.clone().unwrap_or_default(),
+                output.asset.amount.clone().unwrap_or_default()
+            )
+        })
+        .collect()
 }
@@ -0,0 +1,386 @@
+use std::str::FromStr;
+
+use ckb_hash::blake2b_256;
+use ckb_sdk::{Address, AddressPayload};
+use ckb_types::bytes::Bytes;
+use ckb_types::packed::{Script, WitnessArgs};
+use ckb_types::prelude::{Builder, Entity, Pack};
+use ckb_types::H160;
+
+use crate::models::multi_sig_account::MultiSigInfo;
+use crate::models::multi_sig_message::{
+    MultiSigMessage, MultiSigMessageSignature, MESSAGE_STATUS_PENDING,
+};
+use crate::repositories::ckb::{get_ckb_network, get_multisig_script_hash, parse_multisig_config};
+use crate::repositories::multi_sig_account::MultiSigDao;
+use crate::repositories::multi_sig_message::MultiSigMessageDao;
+use crate::serialize::error::AppError;
+use crate::serialize::multi_sig_message::{
+    ListMessagesRes, MessageFilters, MessageInfo, MessageSignatureReq, NewMessageReq,
+    VerifyMessageReq, VerifyMessageRes,
+};
+use crate::serialize::PaginationRes;
+use crate::services::signature::{match_signer, message_digest, recover_signer_args};
+
+const MAX_MESSAGE_LENGTH: usize = 10000;
+
+#[derive(Clone, Debug)]
+pub struct MultiSigMessageSrv {
+    multi_sig_message_dao: MultiSigMessageDao,
+    multi_sig_dao: MultiSigDao,
+}
+
+impl MultiSigMessageSrv {
+    pub fn new(multi_sig_message_dao: MultiSigMessageDao, multi_sig_dao: MultiSigDao) -> Self {
+        MultiSigMessageSrv {
+            multi_sig_message_dao: multi_sig_message_dao.clone(),
+            multi_sig_dao: multi_sig_dao.clone(),
+        }
+    }
+
+    // Only signers of the account can see and sign its messages
+    async fn request_multi_sig_info(
+        &self,
+        multi_sig_address: &str,
+        user_address: &str,
+    ) -> Result<MultiSigInfo, AppError> {
+        self.multi_sig_dao
+            .request_multi_sig_info_by_user(multi_sig_address, user_address)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("not found"))
+    }
+
+    async fn request_message(
+        &self,
+        user_address: &str,
+        id: i32,
+    ) -> Result<(MultiSigInfo, MultiSigMessage), AppError> {
+        let message = self
+            .multi_sig_message_dao
+            .get_message(id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Message not found"))?;
+        let multi_sig_info = self
+            .request_multi_sig_info(&message.multi_sig_address, user_address)
+            .await
+            .map_err(|_| AppError::new(404).message("Message not found"))?;
+        Ok((multi_sig_info, message))
+    }
+
+    /// Decode a 65 bytes signature and check it is the user's own signature of the digest.
+    fn validate_signature(
+        user_address: &str,
+        digest: &[u8; 32],
+        signature: &str,
+    ) -> Result<String, AppError> {
+        let signature = hex::decode(signature.trim_start_matches("0x"))
+            .map_err(|err| AppError::new(400).cause(err).message("Signature Invalid"))?;
+        if match_signer(digest, &signature, &[user_address.to_owned()])?.is_none() {
+            return Err(AppError::new(400).message("Signature does not belong to you"));
+        }
+        Ok(hex::encode(signature))
+    }
+
+    pub async fn create_message(
+        &self,
+        user_address: &str,
+        multi_sig_address: &str,
+        req: NewMessageReq,
+    ) -> Result<MessageInfo, AppError> {
+        let multi_sig_info = self
+            .request_multi_sig_info(multi_sig_address, user_address)
+            .await?;
+
+        if req.message.is_empty() {
+            return Err(AppError::new(400).message("Message must not be empty"));
+        }
+        if req.message.chars().count() > MAX_MESSAGE_LENGTH {
+            return Err(AppError::new(400).message(&format!(
+                "Message must not exceed {} characters",
+                MAX_MESSAGE_LENGTH
+            )));
+        }
+
+        let digest = message_digest(req.message.as_bytes());
+        let signature = Self::validate_signature(user_address, &digest, &req.signature)?;
+
+        let message = self
+            .multi_sig_message_dao
+            .create_message(
+                &multi_sig_info.multi_sig_address,
+                &user_address.to_owned(),
+                &req.message,
+                &hex::encode(digest),
+                &signature,
+            )
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+
+        self.complete_if_ready(&multi_sig_info, message).await
+    }
+
+    pub async fn sign_message(
+        &self,
+        user_address: &str,
+        id: i32,
+        req: MessageSignatureReq,
+    ) -> Result<MessageInfo, AppError> {
+        let (multi_sig_info, message) = self.request_message(user_address, id).await?;
+        if message.status.ne(&MESSAGE_STATUS_PENDING) {
+            return Err(AppError::new(400).message("Message already has enough signatures"));
+        }
+
+        let mut digest = [0u8; 32];
+        hex::decode_to_slice(&message.digest, &mut digest)
+            .map_err(|err| AppError::new(500).cause(err).message("invalid digest"))?;
+        let signature = Self::validate_signature(user_address, &digest, &req.signature)?;
+
+        let added = self
+            .multi_sig_message_dao
+            .add_signature(message.id, &user_address.to_owned(), &signature)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        if !added {
+            return Err(AppError::new(400).message("Message already signed"));
+        }
+
+        self.complete_if_ready(&multi_sig_info, message).await
+    }
+
+    pub async fn get_message(&self, user_address: &str, id: i32) -> Result<MessageInfo, AppError> {
+        let (multi_sig_info, message) = self.request_message(user_address, id).await?;
+        let signatures = self.get_signatures(message.id).await?;
+        Ok(Self::message_info(&multi_sig_info, message, &signatures))
+    }
+
+    pub async fn list_messages(
+        &self,
+        user_address: &str,
+        multi_sig_address: &str,
+        filters: MessageFilters,
+    ) -> Result<ListMessagesRes, AppError> {
+        let multi_sig_info = self
+            .request_multi_sig_info(multi_sig_address, user_address)
+            .await?;
+        let limit: i64 = filters.limit.unwrap_or(10);
+        let page: i64 = filters.page.unwrap_or(1);
+
+        let messages = self
+            .multi_sig_message_dao
+            .get_messages_by_address(&multi_sig_info.multi_sig_address, (page - 1) * limit, limit)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        let mut results = vec![];
+        for message in messages {
+            let signatures = self.get_signatures(message.id).await?;
+            results.push(Self::message_info(&multi_sig_info, message, &signatures));
+        }
+
+        let total_record = self
+            .multi_sig_message_dao
+            .count_messages_by_address(&multi_sig_info.multi_sig_address)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        let total_page = total_record as f64 / limit as f64;
+        Ok(ListMessagesRes {
+            messages: results,
+            pagination: PaginationRes {
+                page,
+                limit,
+                total_records: total_record,
+                total_page: total_page.ceil() as i64,
+            },
+        })
+    }
+
+    /// Check a proof against the multisig config committed in the address. Anyone can
+    /// verify, the account does not have to be known by this server.
+    pub fn verify_message(&self, req: VerifyMessageReq) -> Result<VerifyMessageRes, AppError> {
+        let address = Address::from_str(&req.multi_sig_address)
+            .map_err(|_| AppError::new(400).message("invalid multisig address"))?;
+        let proof = hex::decode(req.proof.trim_start_matches("0x"))
+            .map_err(|err| AppError::new(400).cause(err).message("invalid proof"))?;
+        let digest = message_digest(req.message.as_bytes());
+
+        Ok(
+            match verify_proof(&Script::from(&address), &proof, &digest) {
+                Ok(signers) => VerifyMessageRes {
+                    valid: true,
+                    signers,
+                    reason: None,
+                },
+                Err(reason) => VerifyMessageRes {
+                    valid: false,
+                    signers: vec![],
+                    reason: Some(reason),
+                },
+            },
+        )
+    }
+
+    async fn get_signatures(
+        &self,
+        message_id: i32,
+    ) -> Result<Vec<MultiSigMessageSignature>, AppError> {
+        self.multi_sig_message_dao
+            .get_signatures(message_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))
+    }
+
+    /// Aggregate the proof once the threshold is reached.
+    async fn complete_if_ready(
+        &self,
+        multi_sig_info: &MultiSigInfo,
+        message: MultiSigMessage,
+    ) -> Result<MessageInfo, AppError> {
+        let signatures = self.get_signatures(message.id).await?;
+
+        let mut message = message;
+        if let Some(proof) = build_proof(multi_sig_info, &signatures)? {
+            if let Some(completed) = self
+                .multi_sig_message_dao
+                .complete_message(message.id, &proof)
+                .await
+                .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            {
+                message = completed;
+            }
+        }
+
+        Ok(Self::message_info(multi_sig_info, message, &signatures))
+    }
+
+    fn message_info(
+        multi_sig_info: &MultiSigInfo,
+        message: MultiSigMessage,
+        signatures: &[MultiSigMessageSignature],
+    ) -> MessageInfo {
+        let witness = message
+            .proof
+            .as_ref()
+            .and_then(|proof| hex::decode(proof.trim_start_matches("0x")).ok())
+            .map(|lock| {
+                let witness_args = WitnessArgs::new_builder()
+                    .lock(Some(Bytes::from(lock)).pack())
+                    .build();
+                format!("0x{}", hex::encode(witness_args.as_slice()))
+            });
+
+        MessageInfo {
+            message,
+            threshold: multi_sig_info.threshold,
+            signers: signatures
+                .iter()
+                .map(|signature| signature.signer_address.clone())
+                .collect(),
+            witness,
+        }
+    }
+}
+
+/// The multisig lock proving the message, or `None` while the collected signatures cannot
+/// satisfy the threshold and the required first signers.
+fn build_proof(
+    multi_sig_info: &MultiSigInfo,
+    signatures: &[MultiSigMessageSignature],
+) -> Result<Option<String>, AppError> {
+    let multisig_config = parse_multisig_config(&multi_sig_info.multi_sig_witness_data)?;
+    let sighash_addresses = multisig_config.sighash_addresses();
+
+    // Signatures follow the order of their signer in the config
+    let mut indexed: Vec<(usize, &MultiSigMessageSignature)> = signatures
+        .iter()
+        .filter_map(|signature| {
+            let args = Address::from_str(&signature.signer_address)
+                .ok()?
+                .payload()
+                .args();
+            let index = sighash_addresses
+                .iter()
+                .position(|hash| hash.as_bytes() == args.as_ref())?;
+            Some((index, signature))
+        })
+        .collect();
+    indexed.sort_by_key(|(index, _)| *index);
+
+    let require_first_n = multisig_config.require_first_n() as usize;
+    let threshold = multisig_config.threshold() as usize;
+    let required = indexed
+        .iter()
+        .filter(|(index, _)| *index < require_first_n)
+        .count();
+    if required < require_first_n || indexed.len() < threshold {
+        return Ok(None);
+    }
+
+    let mut lock = hex::decode(&multi_sig_info.multi_sig_witness_data).map_err(|err| {
+        AppError::new(500)
+            .cause(err)
+            .message("invalid multisig config")
+    })?;
+    for (_, signature) in indexed.iter().take(threshold) {
+        lock.extend(
+            hex::decode(&signature.signature)
+                .map_err(|err| AppError::new(500).cause(err).message("invalid signature"))?,
+        );
+    }
+    Ok(Some(format!("0x{}", hex::encode(lock))))
+}
+
+/// Returns the addresses of the signers of a valid proof, or why it is invalid.
+fn verify_proof(lock: &Script, proof: &[u8], digest: &[u8; 32]) -> Result<Vec<String>, String> {
+    if lock.code_hash().as_slice() != get_multisig_script_hash().as_bytes() {
+        return Err("Address is not a multisig address".to_owned());
+    }
+    if proof.len() < 4 || proof[0] != 0 {
+        return Err("Proof does not start with a multisig config".to_owned());
+    }
+
+    let (require_first_n, threshold, pubkeys) =
+        (proof[1] as usize, proof[2] as usize, proof[3] as usize);
+    let config_len = 4 + 20 * pubkeys;
+    if threshold == 0 || threshold > pubkeys || require_first_n > threshold {
+        return Err("Invalid multisig config".to_owned());
+    }
+    if proof.len() != config_len + 65 * threshold {
+        return Err("Proof length does not match the multisig config".to_owned());
+    }
+
+    let args = lock.args().raw_data();
+    if args.len() < 20 || blake2b_256(&proof[0..config_len])[0..20] != args[0..20] {
+        return Err("Multisig config does not match the address".to_owned());
+    }
+
+    let mut signed = vec![];
+    for signature in proof[config_len..].chunks(65) {
+        let signer_args =
+            recover_signer_args(digest, signature).map_err(|_| "Invalid signature".to_owned())?;
+        let index = proof[4..config_len]
+            .chunks(20)
+            .position(|hash| hash == signer_args.as_ref())
+            .ok_or("Signature from a key outside the multisig config".to_owned())?;
+        if signed.contains(&index) {
+            return Err("Duplicate signature".to_owned());
+        }
+        signed.push(index);
+    }
+    if (0..require_first_n).any(|index| !signed.contains(&index)) {
+        return Err("Missing signatures of the required first signers".to_owned());
+    }
+
+    Ok(signed
+        .into_iter()
+        .map(|index| {
+            let hash = H160::from_slice(&proof[4 + 20 * index..24 + 20 * index]).unwrap();
+            Address::new(
+                get_ckb_network(),
+                AddressPayload::from_pubkey_hash(hash),
+                true,
+            )
+            .to_string()
+        })
+        .collect())
+}
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use ckb_types::bytes::Bytes;
+use ckb_types::core::{DepType, ScriptHashType, TransactionBuilder, TransactionView};
+use ckb_types::packed::{CellDep, CellInput, CellOutput, OutPoint, Script};
+use ckb_types::prelude::{Builder, Entity, Pack, Unpack};
+use ckb_types::H256;
+
+use crate::serialize::error::AppError;
+use crate::serialize::neuron::{
+    NeuronCellDep, NeuronInput, NeuronOutPoint, NeuronOutput, NeuronScript, NeuronTransaction,
+};
+
+// Neuron writes numbers as decimal strings, accept hex as well
+fn parse_number(value: &str) -> Result<u64, AppError> {
+    let result = match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse::<u64>(),
+    };
+    result.map_err(|_| AppError::new(400).message(&format!("invalid number {}", value)))
+}
+
+fn parse_hash(value: &str) -> Result<H256, AppError> {
+    H256::from_str(value.trim_start_matches("0x"))
+        .map_err(|_| AppError::new(400).message(&format!("invalid hash {}", value)))
+}
+
+fn parse_bytes(value: &str) -> Result<Bytes, AppError> {
+    hex::decode(value.trim_start_matches("0x"))
+        .map(Bytes::from)
+        .map_err(|_| AppError::new(400).message(&format!("invalid hex {}", value)))
+}
+
+fn to_neuron_out_point(out_point: &OutPoint) -> NeuronOutPoint {
+    let tx_hash: H256 = out_point.tx_hash().unpack();
+    let index: u32 = out_point.index().unpack();
+    NeuronOutPoint {
+        tx_hash: format!("{:#x}", tx_hash),
+        index: index.to_string(),
+    }
+}
+
+fn from_neuron_out_point(out_point: &NeuronOutPoint) -> Result<OutPoint, AppError> {
+    Ok(OutPoint::new_builder()
+        .tx_hash(parse_hash(&out_point.tx_hash)?.pack())
+        .index((parse_number(&out_point.index)? as u32).pack())
+        .build())
+}
+
+fn to_neuron_script(script: &Script) -> NeuronScript {
+    let code_hash: H256 = script.code_hash().unpack();
+    let hash_type = match ScriptHashType::try_from(script.hash_type()) {
+        Ok(ScriptHashType::Type) => "type",
+        Ok(ScriptHashType::Data1) => "data1",
+        Ok(ScriptHashType::Data2) => "data2",
+        _ => "data",
+    };
+    NeuronScript {
+        code_hash: format!("{:#x}", code_hash),
+        args: format!("0x{}", hex::encode(script.args().raw_data())),
+        hash_type: hash_type.to_owned(),
+    }
+}
+
+fn from_neuron_script(script: &NeuronScript) -> Result<Script, AppError> {
+    let hash_type = match script.hash_type.as_str() {
+        "type" => ScriptHashType::Type,
+        "data" => ScriptHashType::Data,
+        "data1" => ScriptHashType::Data1,
+        "data2" => ScriptHashType::Data2,
+        other => {
+            return Err(AppError::new(400).message(&format!("invalid hash type {}", other)));
+        }
+    };
+    Ok(Script::new_builder()
+        .code_hash(parse_hash(&script.code_hash)?.pack())
+        .hash_type(hash_type.into())
+        .args(parse_bytes(&script.args)?.pack())
+        .build())
+}
+
+/// Convert a transaction to Neuron's model. `inputs` are the resolved previous outputs,
+/// Neuron needs their lock to group the inputs it signs.
+pub fn to_neuron_transaction(
+    tx: &TransactionView,
+    inputs: &[Option<(CellOutput, Bytes)>],
+    signatures: HashMap<String, Vec<String>>,
+) -> NeuronTransaction {
+    let hash: H256 = tx.hash().unpack();
+
+    NeuronTransaction {
+        version: tx.version().to_string(),
+        cell_deps: tx
+            .cell_deps_iter()
+            .map(|cell_dep| NeuronCellDep {
+                out_point: to_neuron_out_point(&cell_dep.out_point()),
+                dep_type: match DepType::try_from(cell_dep.dep_type()) {
+                    Ok(DepType::DepGroup) => "depGroup".to_owned(),
+                    _ => "code".to_owned(),
+                },
+            })
+            .collect(),
+        header_deps: tx
+            .header_deps_iter()
+            .map(|header_dep| {
+                let header_dep: H256 = header_dep.unpack();
+                format!("{:#x}", header_dep)
+            })
+            .collect(),
+        inputs: tx
+            .inputs()
+            .into_iter()
+            .enumerate()
+            .map(|(index, input)| {
+                let since: u64 = input.since().unpack();
+                let previous = inputs.get(index).cloned().flatten();
+                NeuronInput {
+                    previous_output: to_neuron_out_point(&input.previous_output()),
+                    since: since.to_string(),
+                    capacity: previous.as_ref().map(|(output, _)| {
+                        let capacity: u64 = output.capacity().unpack();
+                        capacity.to_string()
+                    }),
+                    lock: previous
+                        .as_ref()
+                        .map(|(output, _)| to_neuron_script(&output.lock())),
+                    lock_hash: previous.as_ref().map(|(output, _)| {
+                        format!(
+                            "0x{}",
+                            hex::encode(output.lock().calc_script_hash().raw_data())
+                        )
+                    }),
+                }
+            })
+            .collect(),
+        outputs: tx
+            .outputs()
+            .into_iter()
+            .map(|output| {
+                let capacity: u64 = output.capacity().unpack();
+                NeuronOutput {
+                    capacity: capacity.to_string(),
+                    lock: to_neuron_script(&output.lock()),
+                    type_: output
+                        .type_()
+                        .to_opt()
+                        .map(|type_script| to_neuron_script(&type_script)),
+                }
+            })
+            .collect(),
+        outputs_data: tx
+            .outputs_data()
+            .into_iter()
+            .map(|data| format!("0x{}", hex::encode(data.raw_data())))
+            .collect(),
+        witnesses: tx
+            .witnesses()
+            .into_iter()
+            .map(|witness| format!("0x{}", hex::encode(witness.raw_data())))
+            .collect(),
+        hash: Some(format!("{:#x}", hash)),
+        signatures,
+    }
+}
+
+pub fn from_neuron_transaction(tx: &NeuronTransaction) -> Result<TransactionView, AppError> {
+    let mut builder =
+        TransactionBuilder::default().version((parse_number(&tx.version)? as u32).pack());
+
+    for cell_dep in tx.cell_deps.iter() {
+        let dep_type = match cell_dep.dep_type.as_str() {
+            "depGroup" | "dep_group" => DepType::DepGroup,
+            _ => DepType::Code,
+        };
+        builder = builder.cell_dep(
+            CellDep::new_builder()
+                .out_point(from_neuron_out_point(&cell_dep.out_point)?)
+                .dep_type(dep_type.into())
+                .build(),
+        );
+    }
+    for header_dep in tx.header_deps.iter() {
+        builder = builder.header_dep(parse_hash(header_dep)?.pack());
+    }
+    for input in tx.inputs.iter() {
+        builder = builder.input(CellInput::new(
+            from_neuron_out_point(&input.previous_output)?,
+            parse_number(&input.since)?,
+        ));
+    }
+    for output in tx.outputs.iter() {
+        builder = builder.output(
+            CellOutput::new_builder()
+                .capacity(parse_number(&output.capacity)?.pack())
+                .lock(from_neuron_script(&output.lock)?)
+                .type_(
+                    output
+                        .type_
+                        .as_ref()
+                        .map(from_neuron_script)
+                        .transpose()?
+                        .pack(),
+                )
+                .build(),
+        );
+    }
+    for data in tx.outputs_data.iter() {
+        builder = builder.output_data(parse_bytes(data)?.pack());
+    }
+    for witness in tx.witnesses.iter() {
+        builder = builder.witness(parse_bytes(witness)?.pack());
+    }
+
+    Ok(builder.build())
+}
@@ -0,0 +1,157 @@
+use crate::repositories::notification::NotificationDao;
+use crate::serialize::error::AppError;
+use crate::serialize::event::AccountEvent;
+use crate::serialize::notification::{
+    ListNotificationsRes, MarkReadReq, MarkReadRes, NotificationFilters, UnreadCountRes,
+};
+use crate::serialize::PaginationRes;
+use crate::services::events::{
+    event_summary, EVENT_INVITE_CREATED, EVENT_PROPOSAL_CREATED, EVENT_SIGNATURE_ADDED,
+    EVENT_TRANSACTION_COMMITTED, EVENT_TRANSACTION_FAILED,
+};
+
+const MAX_NOTIFICATIONS_PER_PAGE: i64 = 100;
+const MAX_MARK_READ_IDS: usize = 500;
+
+// Events that land in the inbox, the others are only streamed and pushed
+pub const INBOX_EVENTS: [&str; 5] = [
+    EVENT_INVITE_CREATED,
+    EVENT_PROPOSAL_CREATED,
+    EVENT_SIGNATURE_ADDED,
+    EVENT_TRANSACTION_COMMITTED,
+    EVENT_TRANSACTION_FAILED,
+];
+
+/// Write the inbox entries of an event: the invitee for invites, otherwise every signer of
+/// the account but the one who acted.
+pub async fn record_event_notifications(
+    notification_dao: &NotificationDao,
+    event: &AccountEvent,
+) -> Result<(), AppError> {
+    if !INBOX_EVENTS.contains(&event.event.as_str()) {
+        return Ok(());
+    }
+
+    let summary = event_summary(event);
+    if event.event == EVENT_INVITE_CREATED {
+        if let Some(invitee) = &event.actor_address {
+            notification_dao
+                .create_notification(invitee, event, &summary.title, &summary.body, &summary.link)
+                .await
+                .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        }
+        return Ok(());
+    }
+
+    notification_dao
+        .create_account_notifications(event, &summary.title, &summary.body, &summary.link)
+        .await
+        .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+    Ok(())
+}
+
+#[derive(Clone, Debug)]
+pub struct NotificationSrv {
+    notification_dao: NotificationDao,
+}
+
+impl NotificationSrv {
+    pub fn new(notification_dao: NotificationDao) -> Self {
+        NotificationSrv {
+            notification_dao: notification_dao.clone(),
+        }
+    }
+
+    async fn unread_count(&self, user_address: &String) -> Result<i64, AppError> {
+        self.notification_dao
+            .count_notifications(user_address, true)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))
+    }
+
+    pub async fn list_notifications(
+        &self,
+        user_address: &str,
+        filters: NotificationFilters,
+    ) -> Result<ListNotificationsRes, AppError> {
+        let user_address = user_address.to_owned();
+        let limit: i64 = filters.limit.unwrap_or(10);
+        let page: i64 = filters.page.unwrap_or(1);
+        if !(1..=MAX_NOTIFICATIONS_PER_PAGE).contains(&limit) || page < 1 {
+            return Err(AppError::new(400).message("Invalid page or limit"));
+        }
+        let unread_only = filters.unread.unwrap_or(false);
+
+        let notifications = self
+            .notification_dao
+            .get_notifications(&user_address, unread_only, (page - 1) * limit, limit)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        let total_record = self
+            .notification_dao
+            .count_notifications(&user_address, unread_only)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        let total_page = total_record as f64 / limit as f64;
+        let unread_count = if unread_only {
+            total_record
+        } else {
+            self.unread_count(&user_address).await?
+        };
+
+        Ok(ListNotificationsRes {
+            notifications,
+            pagination: PaginationRes {
+                page,
+                limit,
+                total_records: total_record,
+                total_page: total_page.ceil() as i64,
+            },
+            unread_count,
+        })
+    }
+
+    pub async fn get_unread_count(&self, user_address: &str) -> Result<UnreadCountRes, AppError> {
+        let unread_count = self.unread_count(&user_address.to_owned()).await?;
+        Ok(UnreadCountRes { unread_count })
+    }
+
+    pub async fn mark_read(
+        &self,
+        user_address: &str,
+        req: MarkReadReq,
+    ) -> Result<MarkReadRes, AppError> {
+        let user_address = user_address.to_owned();
+        if req.ids.is_empty() {
+            return Err(AppError::new(400).message("ids is required"));
+        }
+        if req.ids.len() > MAX_MARK_READ_IDS {
+            return Err(AppError::new(400).message(&format!(
+                "Can not mark more than {} notifications at once",
+                MAX_MARK_READ_IDS
+            )));
+        }
+
+        let updated = self
+            .notification_dao
+            .mark_read(&user_address, &req.ids)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        Ok(MarkReadRes {
+            updated,
+            unread_count: self.unread_count(&user_address).await?,
+        })
+    }
+
+    pub async fn mark_all_read(&self, user_address: &str) -> Result<MarkReadRes, AppError> {
+        let updated = self
+            .notification_dao
+            .mark_all_read(&user_address.to_owned())
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        Ok(MarkReadRes {
+            updated,
+            unread_count: 0,
+        })
+    }
+}
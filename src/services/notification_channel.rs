@@ -0,0 +1,218 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::config;
+use crate::models::multi_sig_account::MultiSigInfo;
+use crate::models::notification_channel::NotificationChannel;
+use crate::repositories::multi_sig_account::MultiSigDao;
+use crate::repositories::notification_channel::NotificationChannelDao;
+use crate::serialize::error::AppError;
+use crate::serialize::event::AccountEvent;
+use crate::serialize::notification_channel::{NewChannelReq, UpdateChannelReq};
+use crate::services::events::{
+    event_summary, ACCOUNT_EVENTS, EVENT_PROPOSAL_CREATED, EVENT_THRESHOLD_REACHED,
+    EVENT_TRANSACTION_BROADCAST, EVENT_TRANSACTION_COMMITTED, EVENT_TRANSACTION_FAILED,
+    EVENT_TRANSACTION_REJECTED,
+};
+use crate::services::notifier::{notifier, send_notification};
+
+const MAX_CHANNELS_PER_ACCOUNT: usize = 10;
+
+// Proposal and execution events, what a linked channel gets unless told otherwise
+pub const CHANNEL_DEFAULT_EVENTS: [&str; 6] = [
+    EVENT_PROPOSAL_CREATED,
+    EVENT_THRESHOLD_REACHED,
+    EVENT_TRANSACTION_BROADCAST,
+    EVENT_TRANSACTION_COMMITTED,
+    EVENT_TRANSACTION_FAILED,
+    EVENT_TRANSACTION_REJECTED,
+];
+
+pub fn notifier_client() -> reqwest::Client {
+    let timeout_secs: u64 = config::get("notifier_timeout_secs");
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(timeout_secs))
+        .build()
+        .unwrap()
+}
+
+#[derive(Clone, Debug)]
+pub struct NotificationChannelSrv {
+    notification_channel_dao: NotificationChannelDao,
+    multi_sig_dao: MultiSigDao,
+    client: reqwest::Client,
+}
+
+impl NotificationChannelSrv {
+    pub fn new(
+        notification_channel_dao: NotificationChannelDao,
+        multi_sig_dao: MultiSigDao,
+    ) -> Self {
+        NotificationChannelSrv {
+            notification_channel_dao: notification_channel_dao.clone(),
+            multi_sig_dao: multi_sig_dao.clone(),
+            client: notifier_client(),
+        }
+    }
+
+    // Only signers of the account can manage its channels
+    async fn request_multi_sig_info(
+        &self,
+        multi_sig_address: &str,
+        user_address: &str,
+    ) -> Result<MultiSigInfo, AppError> {
+        self.multi_sig_dao
+            .request_multi_sig_info_by_user(multi_sig_address, user_address)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("not found"))
+    }
+
+    async fn request_channel(
+        &self,
+        user_address: &str,
+        id: i32,
+    ) -> Result<NotificationChannel, AppError> {
+        let channel = self
+            .notification_channel_dao
+            .get_channel(id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Channel not found"))?;
+        self.request_multi_sig_info(&channel.multi_sig_address, user_address)
+            .await
+            .map_err(|_| AppError::new(404).message("Channel not found"))?;
+        Ok(channel)
+    }
+
+    fn validate_events(events: &[String]) -> Result<Vec<String>, AppError> {
+        if events.is_empty() {
+            return Ok(CHANNEL_DEFAULT_EVENTS
+                .iter()
+                .map(|event| event.to_string())
+                .collect());
+        }
+
+        let mut validated: Vec<String> = vec![];
+        for event in events {
+            if !ACCOUNT_EVENTS.contains(&event.as_str()) {
+                return Err(AppError::new(400).message(&format!("Unknown event {}", event)));
+            }
+            if !validated.contains(event) {
+                validated.push(event.clone());
+            }
+        }
+        Ok(validated)
+    }
+
+    pub async fn list_channels(
+        &self,
+        user_address: &str,
+        multi_sig_address: &str,
+    ) -> Result<Vec<NotificationChannel>, AppError> {
+        let multi_sig_info = self
+            .request_multi_sig_info(multi_sig_address, user_address)
+            .await?;
+
+        self.notification_channel_dao
+            .get_channels_by_address(&multi_sig_info.multi_sig_address)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))
+    }
+
+    pub async fn create_channel(
+        &self,
+        user_address: &str,
+        multi_sig_address: &str,
+        req: NewChannelReq,
+    ) -> Result<NotificationChannel, AppError> {
+        let multi_sig_info = self
+            .request_multi_sig_info(multi_sig_address, user_address)
+            .await?;
+        let kind = req.kind.trim().to_lowercase();
+        let target = req.target.trim().to_owned();
+        notifier(&kind)?.validate_target(&target)?;
+        let events = Self::validate_events(&req.events)?;
+
+        let channels = self
+            .notification_channel_dao
+            .get_channels_by_address(&multi_sig_info.multi_sig_address)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        if channels.len() >= MAX_CHANNELS_PER_ACCOUNT {
+            return Err(AppError::new(400).message(&format!(
+                "An account can not have more than {} channels",
+                MAX_CHANNELS_PER_ACCOUNT
+            )));
+        }
+
+        self.notification_channel_dao
+            .create_channel(
+                &multi_sig_info.multi_sig_address,
+                &kind,
+                &target,
+                &events,
+                &user_address.to_owned(),
+            )
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))
+    }
+
+    pub async fn update_channel(
+        &self,
+        user_address: &str,
+        id: i32,
+        req: UpdateChannelReq,
+    ) -> Result<NotificationChannel, AppError> {
+        let channel = self.request_channel(user_address, id).await?;
+        let target = match req.target {
+            Some(target) => {
+                let target = target.trim().to_owned();
+                notifier(&channel.kind)?.validate_target(&target)?;
+                target
+            }
+            None => channel.target,
+        };
+        let events = match req.events {
+            Some(events) => Self::validate_events(&events)?,
+            None => channel.events,
+        };
+
+        self.notification_channel_dao
+            .update_channel(
+                channel.id,
+                &target,
+                &events,
+                req.active.unwrap_or(channel.active),
+            )
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))
+    }
+
+    pub async fn delete_channel(&self, user_address: &str, id: i32) -> Result<bool, AppError> {
+        let channel = self.request_channel(user_address, id).await?;
+
+        self.notification_channel_dao
+            .delete_channel(channel.id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))
+    }
+
+    /// Post a sample proposal event, to check the channel is linked right.
+    pub async fn test_channel(&self, user_address: &str, id: i32) -> Result<bool, AppError> {
+        let channel = self.request_channel(user_address, id).await?;
+        let event = AccountEvent {
+            id: Uuid::new_v4().to_string(),
+            event: EVENT_PROPOSAL_CREATED.to_owned(),
+            multi_sig_address: channel.multi_sig_address.clone(),
+            transaction_id: None,
+            actor_address: Some(user_address.to_owned()),
+            created_at: Utc::now().timestamp(),
+        };
+
+        send_notification(&self.client, &channel, &event_summary(&event)).await?;
+        Ok(true)
+    }
+}
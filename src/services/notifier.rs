@@ -0,0 +1,194 @@
+use reqwest::Url;
+use serde_json::{json, Value};
+
+use crate::config;
+use crate::models::notification_channel::{
+    NotificationChannel, CHANNEL_KIND_DISCORD, CHANNEL_KIND_SLACK, CHANNEL_KIND_TELEGRAM,
+};
+use crate::serialize::error::AppError;
+use crate::services::events::EventSummary;
+
+// Accent of the Discord embeds, the orange of the app
+const DISCORD_EMBED_COLOR: u32 = 0xff7201;
+
+/// A chat service account events can be posted to. Implementations only shape the request,
+/// `send_notification` sends it.
+pub trait Notifier: Send + Sync {
+    /// Check a channel target before it is saved.
+    fn validate_target(&self, target: &str) -> Result<(), AppError>;
+
+    fn endpoint(&self, target: &str) -> String;
+
+    fn payload(&self, target: &str, summary: &EventSummary) -> Value;
+}
+
+/// Posts with the Bot API, the target is a chat id or an `@channel` username.
+pub struct TelegramNotifier {
+    api_url: String,
+    bot_token: String,
+}
+
+impl Notifier for TelegramNotifier {
+    fn validate_target(&self, target: &str) -> Result<(), AppError> {
+        if self.bot_token.is_empty() {
+            return Err(AppError::new(400).message("Telegram notifications are not configured"));
+        }
+        let valid = match target.strip_prefix('@') {
+            Some(username) => !username.is_empty(),
+            None => target.parse::<i64>().is_ok(),
+        };
+        if !valid {
+            return Err(AppError::new(400).message("Telegram target must be a chat id or @channel"));
+        }
+        Ok(())
+    }
+
+    fn endpoint(&self, _target: &str) -> String {
+        format!(
+            "{}/bot{}/sendMessage",
+            self.api_url.trim_end_matches('/'),
+            self.bot_token
+        )
+    }
+
+    fn payload(&self, target: &str, summary: &EventSummary) -> Value {
+        let escape = |value: &str| {
+            value
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+        };
+        json!({
+            "chat_id": target,
+            "parse_mode": "HTML",
+            "disable_web_page_preview": true,
+            "text": format!(
+                "<b>{}</b>\n{}\n<a href=\"{}\">{}</a>",
+                escape(&summary.title),
+                escape(&summary.body),
+                escape(&summary.link).replace('"', "&quot;"),
+                escape(&summary.action_label)
+            ),
+        })
+    }
+}
+
+/// Posts to a Discord incoming webhook, the target is the webhook url.
+pub struct DiscordNotifier {
+    webhook_base_url: String,
+}
+
+impl Notifier for DiscordNotifier {
+    fn validate_target(&self, target: &str) -> Result<(), AppError> {
+        validate_webhook_url(target, &self.webhook_base_url, "Discord")
+    }
+
+    fn endpoint(&self, target: &str) -> String {
+        target.to_owned()
+    }
+
+    fn payload(&self, _target: &str, summary: &EventSummary) -> Value {
+        json!({
+            "embeds": [{
+                "title": summary.title,
+                "description": summary.body,
+                "url": summary.link,
+                "color": DISCORD_EMBED_COLOR,
+            }],
+        })
+    }
+}
+
+/// Posts to a Slack compatible incoming webhook, the target is the webhook url.
+pub struct SlackNotifier {
+    webhook_base_url: String,
+}
+
+impl Notifier for SlackNotifier {
+    fn validate_target(&self, target: &str) -> Result<(), AppError> {
+        validate_webhook_url(target, &self.webhook_base_url, "Slack")
+    }
+
+    fn endpoint(&self, target: &str) -> String {
+        target.to_owned()
+    }
+
+    fn payload(&self, _target: &str, summary: &EventSummary) -> Value {
+        let escape = |value: &str| {
+            value
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+        };
+        json!({
+            // shown in notifications, where blocks are not rendered
+            "text": format!("{}: {}", summary.title, summary.link),
+            "blocks": [{
+                "type": "section",
+                "text": {
+                    "type": "mrkdwn",
+                    "text": format!(
+                        "*{}*\n{}\n<{}|{}>",
+                        escape(&summary.title),
+                        escape(&summary.body),
+                        summary.link,
+                        escape(&summary.action_label)
+                    ),
+                },
+            }],
+        })
+    }
+}
+
+// Webhook urls are restricted to the configured service, so links can't make the API
+// post anywhere else
+fn validate_webhook_url(target: &str, base_url: &str, service: &str) -> Result<(), AppError> {
+    let invalid = || AppError::new(400).message(&format!("Invalid {} webhook url", service));
+
+    let url = Url::parse(target).map_err(|_| invalid())?;
+    if !url.as_str().starts_with(base_url) || url.as_str().len() == base_url.len() {
+        return Err(invalid());
+    }
+    Ok(())
+}
+
+pub fn notifier(kind: &str) -> Result<Box<dyn Notifier>, AppError> {
+    match kind {
+        CHANNEL_KIND_TELEGRAM => Ok(Box::new(TelegramNotifier {
+            api_url: config::get("telegram_api_url"),
+            bot_token: config::get("telegram_bot_token"),
+        })),
+        CHANNEL_KIND_DISCORD => Ok(Box::new(DiscordNotifier {
+            webhook_base_url: config::get("discord_webhook_base_url"),
+        })),
+        CHANNEL_KIND_SLACK => Ok(Box::new(SlackNotifier {
+            webhook_base_url: config::get("slack_webhook_base_url"),
+        })),
+        _ => Err(AppError::new(400).message("Channel kind must be telegram, discord or slack")),
+    }
+}
+
+pub async fn send_notification(
+    client: &reqwest::Client,
+    channel: &NotificationChannel,
+    summary: &EventSummary,
+) -> Result<(), AppError> {
+    let notifier = notifier(&channel.kind)?;
+    let response = client
+        .post(notifier.endpoint(&channel.target))
+        .json(&notifier.payload(&channel.target, summary))
+        .send()
+        .await
+        .map_err(|err| {
+            // The endpoint carries the bot token or the webhook secret, keep it out of
+            // both the log and the response
+            log::warn!("{} request failed: {}", channel.kind, err.without_url());
+            AppError::new(502).message(&format!("{} request failed", channel.kind))
+        })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(AppError::new(502).message(&format!("{} responded {}", channel.kind, status)));
+    }
+    Ok(())
+}
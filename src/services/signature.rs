@@ -0,0 +1,214 @@
+use std::str::FromStr;
+
+use ckb_hash::blake2b_256;
+use ckb_sdk::unlock::generate_message;
+use ckb_sdk::{Address, AddressPayload, ScriptGroup, ScriptGroupType};
+use ckb_types::bytes::Bytes;
+use ckb_types::core::TransactionView;
+use ckb_types::packed::{CellOutput, Script, WitnessArgs};
+use ckb_types::prelude::{Entity, Unpack};
+use secp256k1::{
+    ecdsa::{RecoverableSignature, RecoveryId},
+    Message, Secp256k1,
+};
+
+use crate::models::multi_sig_account::MultiSigInfo;
+use crate::repositories::ckb::get_transaction_body;
+use crate::serialize::error::AppError;
+use crate::services::cobuild::WitnessLayout;
+
+const MESSAGE_PREFIX: &[u8] = b"Nervos Message:";
+
+/// The message signers of a multisig proposal sign: the sighash-all digest of the
+/// transaction with a zeroed multisig lock in the first witness.
+pub fn multisig_signing_message(
+    multi_sig_info: &MultiSigInfo,
+    tx: &TransactionView,
+) -> Result<[u8; 32], AppError> {
+    let address = Address::from_str(&multi_sig_info.multi_sig_address)
+        .map_err(|_| AppError::new(500).message("invalid multisig address"))?;
+    let config_data = hex::decode(&multi_sig_info.multi_sig_witness_data).map_err(|err| {
+        AppError::new(500)
+            .cause(err)
+            .message("invalid multisig config")
+    })?;
+    let mut zero_lock = config_data.clone();
+    zero_lock.resize(
+        config_data.len() + 65 * multi_sig_info.threshold as usize,
+        0,
+    );
+
+    // All inputs belong to the multi-sig address, the same assumption as add_signature_to_witness
+    let script_group = ScriptGroup {
+        script: Script::from(&address),
+        group_type: ScriptGroupType::Lock,
+        input_indices: (0..tx.inputs().len()).collect(),
+        output_indices: vec![],
+    };
+    let tx = if tx.witnesses().is_empty() {
+        tx.as_advanced_builder()
+            .witness(ckb_types::packed::Bytes::default())
+            .build()
+    } else {
+        tx.clone()
+    };
+
+    let message = generate_message(&tx, &script_group, Bytes::from(zero_lock)).map_err(|err| {
+        AppError::new(400)
+            .cause(err)
+            .message("cannot generate signing message")
+    })?;
+    let mut result = [0u8; 32];
+    result.copy_from_slice(&message);
+    Ok(result)
+}
+
+/// The system multisig lock reads its signatures from the `WitnessArgs` of the first
+/// witness, a CoBuild layout there could never unlock the inputs. CoBuild is only
+/// supported as the `BuildingPacket` transport of a proposal.
+pub fn ensure_witness_args(tx: &TransactionView) -> Result<(), AppError> {
+    let first_witness = tx.witnesses().get(0).map(|witness| witness.raw_data());
+    if first_witness.is_some_and(|witness| WitnessLayout::from_witness(&witness).is_some()) {
+        return Err(AppError::new(400)
+            .message("The multisig lock can not be sealed in a CoBuild witness layout"));
+    }
+    Ok(())
+}
+
+/// The message signers of a proposal sign.
+pub fn proposal_signing_message(
+    multi_sig_info: &MultiSigInfo,
+    tx: &TransactionView,
+) -> Result<[u8; 32], AppError> {
+    ensure_witness_args(tx)?;
+    multisig_signing_message(multi_sig_info, tx)
+}
+
+/// The CoBuild `Message` of a transaction, carried by at most one `SighashAll` witness.
+pub fn cobuild_message(tx: &TransactionView) -> Result<Option<Bytes>, AppError> {
+    let mut message = None;
+    for witness in tx.witnesses() {
+        if let Some(WitnessLayout::SighashAll { message: found, .. }) =
+            WitnessLayout::from_witness(&witness.raw_data()).transpose()?
+        {
+            if message.is_some() {
+                return Err(AppError::new(400).message("Transaction has more than one message"));
+            }
+            message = Some(found);
+        }
+    }
+    Ok(message)
+}
+
+/// Fetch the cells and data consumed by the inputs of a transaction from their
+/// previous transactions, which keeps working once the inputs are spent.
+pub async fn resolve_input_cells(
+    tx: &TransactionView,
+) -> Result<Vec<(CellOutput, Bytes)>, AppError> {
+    let mut inputs = vec![];
+    for out_point in tx.input_pts_iter() {
+        let index: u32 = out_point.index().unpack();
+        let previous_tx = get_transaction_body(out_point.tx_hash().unpack())
+            .await
+            .map_err(|err| {
+                AppError::new(500)
+                    .cause(err)
+                    .message("get transaction failed")
+            })?
+            .ok_or(AppError::new(400).message("input transaction not found"))?;
+
+        let output = previous_tx
+            .outputs
+            .get(index as usize)
+            .cloned()
+            .ok_or(AppError::new(400).message("input cell not found"))?;
+        let data = previous_tx
+            .outputs_data
+            .get(index as usize)
+            .map(|data| data.clone().into_bytes())
+            .unwrap_or_default();
+        inputs.push((CellOutput::from(output), data));
+    }
+    Ok(inputs)
+}
+
+/// Digest signed for off-chain messages, the convention of Neuron and other CKB wallets.
+pub fn message_digest(message: &[u8]) -> [u8; 32] {
+    let mut data = MESSAGE_PREFIX.to_vec();
+    data.extend_from_slice(message);
+    blake2b_256(data)
+}
+
+/// Recover the sighash lock args (blake160 of the public key) of a 65 bytes recoverable
+/// signature.
+pub fn recover_signer_args(message: &[u8; 32], signature: &[u8]) -> Result<Bytes, AppError> {
+    if signature.len() != 65 {
+        return Err(AppError::new(400).message("Signature must be 65 bytes"));
+    }
+
+    let secp_message = Message::from_digest_slice(message)
+        .map_err(|err| AppError::new(400).cause(err).message("invalid message"))?;
+    let rec_id = RecoveryId::from_i32(signature[64] as i32)
+        .map_err(|err| AppError::new(400).cause(err).message("invalid recovery id"))?;
+    let rec_sig = RecoverableSignature::from_compact(&signature[0..64], rec_id)
+        .map_err(|err| AppError::new(400).cause(err).message("invalid signature"))?;
+    let pub_key = Secp256k1::new()
+        .recover_ecdsa(&secp_message, &rec_sig)
+        .map_err(|err| AppError::new(400).cause(err).message("invalid signature"))?;
+
+    Ok(AddressPayload::from_pubkey(&pub_key).args())
+}
+
+/// Find which of the given signer addresses produced the signature.
+pub fn match_signer(
+    message: &[u8; 32],
+    signature: &[u8],
+    signer_addresses: &[String],
+) -> Result<Option<String>, AppError> {
+    let args = recover_signer_args(message, signature)?;
+    Ok(signer_addresses
+        .iter()
+        .find(|signer_address| {
+            Address::from_str(signer_address)
+                .map(|address| address.payload().args() == args)
+                .unwrap_or(false)
+        })
+        .cloned())
+}
+
+/// Read the signatures filled into the multisig lock of the first witness. The lock must
+/// start with the account's multisig config, empty slots are skipped.
+pub fn extract_witness_signatures(
+    multi_sig_info: &MultiSigInfo,
+    tx: &TransactionView,
+) -> Result<Vec<Vec<u8>>, AppError> {
+    let config_data = hex::decode(&multi_sig_info.multi_sig_witness_data).map_err(|err| {
+        AppError::new(500)
+            .cause(err)
+            .message("invalid multisig config")
+    })?;
+
+    let witness = tx
+        .witnesses()
+        .get(0)
+        .ok_or(AppError::new(400).message("Transaction has no witness"))?;
+    ensure_witness_args(tx)?;
+    let lock = WitnessArgs::from_slice(&witness.raw_data())
+        .map_err(|err| AppError::new(400).cause(err).message("invalid witness"))?
+        .lock()
+        .to_opt()
+        .map(|lock| lock.raw_data())
+        .ok_or(AppError::new(400).message("Witness has no lock"))?;
+
+    if lock.len() != config_data.len() + 65 * multi_sig_info.threshold as usize
+        || lock[0..config_data.len()] != config_data[..]
+    {
+        return Err(AppError::new(400).message("Witness lock does not match the multisig config"));
+    }
+
+    Ok(lock[config_data.len()..]
+        .chunks(65)
+        .filter(|signature| signature.iter().any(|byte| *byte != 0))
+        .map(|signature| signature.to_vec())
+        .collect())
+}
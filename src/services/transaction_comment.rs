@@ -0,0 +1,102 @@
+use crate::models::transaction_comment::TransactionComment;
+use crate::repositories::multi_sig_account::MultiSigDao;
+use crate::repositories::transaction_comment::TransactionCommentDao;
+use crate::serialize::error::AppError;
+use crate::serialize::transaction_comment::TransactionCommentReq;
+
+const MAX_COMMENT_LENGTH: usize = 2000;
+
+#[derive(Clone, Debug)]
+pub struct TransactionCommentSrv {
+    transaction_comment_dao: TransactionCommentDao,
+    multi_sig_dao: MultiSigDao,
+}
+
+impl TransactionCommentSrv {
+    pub fn new(transaction_comment_dao: TransactionCommentDao, multi_sig_dao: MultiSigDao) -> Self {
+        TransactionCommentSrv {
+            transaction_comment_dao: transaction_comment_dao.clone(),
+            multi_sig_dao: multi_sig_dao.clone(),
+        }
+    }
+
+    // Only signers of the multi-sig account owning the transaction can see its thread
+    async fn validate_member(&self, user_address: &str, txid: &str) -> Result<String, AppError> {
+        let transaction = self
+            .multi_sig_dao
+            .get_tx_by_hash_and_signer(user_address, txid)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Transaction not found"))?;
+        Ok(transaction.transaction_id)
+    }
+
+    fn validate_body(req: &TransactionCommentReq) -> Result<String, AppError> {
+        let body = req.body.trim();
+        if body.is_empty() {
+            return Err(AppError::new(400).message("Comment must not be empty"));
+        }
+        if body.chars().count() > MAX_COMMENT_LENGTH {
+            return Err(AppError::new(400).message(&format!(
+                "Comment must not exceed {} characters",
+                MAX_COMMENT_LENGTH
+            )));
+        }
+        Ok(body.to_owned())
+    }
+
+    pub async fn get_comments(
+        &self,
+        user_address: &str,
+        txid: &str,
+    ) -> Result<Vec<TransactionComment>, AppError> {
+        let transaction_id = self.validate_member(user_address, txid).await?;
+
+        self.transaction_comment_dao
+            .get_comments_by_txid(&transaction_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))
+    }
+
+    pub async fn add_comment(
+        &self,
+        user_address: &str,
+        txid: &str,
+        req: TransactionCommentReq,
+    ) -> Result<TransactionComment, AppError> {
+        let transaction_id = self.validate_member(user_address, txid).await?;
+        let body = Self::validate_body(&req)?;
+
+        self.transaction_comment_dao
+            .add_comment(&transaction_id, &user_address.to_owned(), &body)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))
+    }
+
+    pub async fn update_comment(
+        &self,
+        user_address: &str,
+        txid: &str,
+        id: i32,
+        req: TransactionCommentReq,
+    ) -> Result<TransactionComment, AppError> {
+        let transaction_id = self.validate_member(user_address, txid).await?;
+        let body = Self::validate_body(&req)?;
+
+        let comment = self
+            .transaction_comment_dao
+            .get_comment(&transaction_id, id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Comment not found"))?;
+        if comment.author_address != user_address {
+            return Err(AppError::new(403).message("Only the author can edit this comment"));
+        }
+
+        self.transaction_comment_dao
+            .update_comment(comment.id, &user_address.to_owned(), &body)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Comment not found"))
+    }
+}
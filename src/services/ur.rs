@@ -0,0 +1,394 @@
+// Uniform Resources (BCR-2020-005) for air-gapped signers talking through animated QR
+// codes. Only what those signers need is implemented: minimal bytewords, a definite
+// length CBOR subset and the sequential multi-part fragments. Fountain (mixed) parts are
+// skipped when decoding, the pure parts alone always rebuild the message.
+//
+// CBOR schema of the CKB registry types:
+//
+// ckb-sign-request = {
+//     1: bytes .size 32,  ; request id, the transaction hash
+//     2: bytes,           ; molecule `Transaction`, the multisig lock zeroed in the first witness
+//     3: bytes .size 32,  ; signing message
+//     4: text,            ; multisig address
+//     5: bytes,           ; multisig config `S | R | M | N | blake160(pubkey) * N`
+//     ? 6: text,          ; origin
+// }
+//
+// ckb-signature = {
+//     1: bytes .size 32,  ; request id of the answered ckb-sign-request
+//     2: bytes .size 65,  ; recoverable secp256k1 signature of the signing message
+// }
+use crate::serialize::error::AppError;
+
+pub const UR_TYPE_SIGN_REQUEST: &str = "ckb-sign-request";
+pub const UR_TYPE_SIGNATURE: &str = "ckb-signature";
+pub const UR_ORIGIN: &str = "UTXO Global";
+
+pub const DEFAULT_FRAGMENT_LEN: usize = 200;
+pub const MAX_FRAGMENT_LEN: usize = 1000;
+const MIN_FRAGMENT_LEN: usize = 10;
+// Bounds the fragment table allocated from a client supplied header
+const MAX_SEQ_LEN: usize = 1000;
+
+const BYTEWORDS: [&str; 256] = [
+    "able", "acid", "also", "apex", "aqua", "arch", "atom", "aunt", "away", "axis", "back", "bald",
+    "barn", "belt", "beta", "bias", "blue", "body", "brag", "brew", "bulb", "buzz", "calm", "cash",
+    "cats", "chef", "city", "claw", "code", "cola", "cook", "cost", "crux", "curl", "cusp", "cyan",
+    "dark", "data", "days", "deli", "dice", "diet", "door", "down", "draw", "drop", "drum", "dull",
+    "duty", "each", "easy", "echo", "edge", "epic", "even", "exam", "exit", "eyes", "fact", "fair",
+    "fern", "figs", "film", "fish", "fizz", "flap", "flew", "flux", "foxy", "free", "frog", "fuel",
+    "fund", "gala", "game", "gear", "gems", "gift", "girl", "glow", "good", "gray", "grim", "guru",
+    "gush", "gyro", "half", "hang", "hard", "hawk", "heat", "help", "high", "hill", "holy", "hope",
+    "horn", "huts", "iced", "idea", "idle", "inch", "inky", "into", "iris", "iron", "item", "jade",
+    "jazz", "join", "jolt", "jowl", "judo", "jugs", "jump", "junk", "jury", "keep", "keno", "kept",
+    "keys", "kick", "kiln", "king", "kite", "kiwi", "knob", "lamb", "lava", "lazy", "leaf", "legs",
+    "liar", "limp", "lion", "list", "logo", "loud", "love", "luau", "luck", "lung", "main", "many",
+    "math", "maze", "memo", "menu", "meow", "mild", "mint", "miss", "monk", "nail", "navy", "need",
+    "news", "next", "noon", "note", "numb", "obey", "oboe", "omit", "onyx", "open", "oval", "owls",
+    "paid", "part", "peck", "play", "plus", "poem", "pool", "pose", "puff", "puma", "purr", "quad",
+    "quiz", "race", "ramp", "real", "redo", "rich", "road", "rock", "roof", "ruby", "ruin", "runs",
+    "rust", "safe", "saga", "scar", "sets", "silk", "skew", "slot", "soap", "solo", "song", "stub",
+    "surf", "swan", "taco", "task", "taxi", "tent", "tied", "time", "tiny", "toil", "tomb", "toys",
+    "trip", "tuna", "twin", "ugly", "undo", "unit", "urge", "user", "vast", "very", "veto", "vial",
+    "vibe", "view", "visa", "void", "vows", "wall", "wand", "warm", "wasp", "wave", "waxy", "webs",
+    "what", "when", "whiz", "wolf", "work", "yank", "yawn", "yell", "yoga", "yurt", "zaps", "zero",
+    "zest", "zinc", "zone", "zoom",
+];
+
+fn invalid(message: &str) -> AppError {
+    AppError::new(400).message(message)
+}
+
+/// Minimal bytewords: the first and last letter of each word, followed by the CRC32
+/// of the data.
+pub fn bytewords_encode(data: &[u8]) -> String {
+    let checksum = crc32fast::hash(data).to_be_bytes();
+    data.iter()
+        .chain(checksum.iter())
+        .map(|byte| {
+            let word = BYTEWORDS[*byte as usize].as_bytes();
+            format!("{}{}", word[0] as char, word[3] as char)
+        })
+        .collect()
+}
+
+pub fn bytewords_decode(text: &str) -> Result<Vec<u8>, AppError> {
+    let text = text.to_lowercase();
+    if !text.is_ascii() || !text.len().is_multiple_of(2) || text.len() < 10 {
+        return Err(invalid("invalid bytewords"));
+    }
+
+    let mut data = vec![];
+    for pair in text.as_bytes().chunks(2) {
+        let byte = BYTEWORDS
+            .iter()
+            .position(|word| word.as_bytes()[0] == pair[0] && word.as_bytes()[3] == pair[1])
+            .ok_or(invalid("invalid bytewords"))?;
+        data.push(byte as u8);
+    }
+
+    let checksum = data.split_off(data.len() - 4);
+    if crc32fast::hash(&data).to_be_bytes()[..] != checksum[..] {
+        return Err(invalid("invalid bytewords checksum"));
+    }
+    Ok(data)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cbor {
+    Unsigned(u64),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Cbor>),
+    Map(Vec<(Cbor, Cbor)>),
+}
+
+impl Cbor {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = vec![];
+        self.encode_into(&mut data);
+        data
+    }
+
+    fn encode_into(&self, data: &mut Vec<u8>) {
+        match self {
+            Cbor::Unsigned(value) => encode_head(data, 0, *value),
+            Cbor::Bytes(bytes) => {
+                encode_head(data, 2, bytes.len() as u64);
+                data.extend_from_slice(bytes);
+            }
+            Cbor::Text(text) => {
+                encode_head(data, 3, text.len() as u64);
+                data.extend_from_slice(text.as_bytes());
+            }
+            Cbor::Array(items) => {
+                encode_head(data, 4, items.len() as u64);
+                for item in items {
+                    item.encode_into(data);
+                }
+            }
+            Cbor::Map(entries) => {
+                encode_head(data, 5, entries.len() as u64);
+                for (key, value) in entries {
+                    key.encode_into(data);
+                    value.encode_into(data);
+                }
+            }
+        }
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Cbor, AppError> {
+        let mut offset = 0;
+        let value = decode_item(data, &mut offset, 0)?;
+        if offset != data.len() {
+            return Err(invalid("trailing CBOR data"));
+        }
+        Ok(value)
+    }
+
+    pub fn as_unsigned(&self) -> Option<u64> {
+        match self {
+            Cbor::Unsigned(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Cbor::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// Value of an integer key of a map.
+    pub fn get(&self, key: u64) -> Option<&Cbor> {
+        match self {
+            Cbor::Map(entries) => entries
+                .iter()
+                .find(|(entry_key, _)| entry_key.as_unsigned() == Some(key))
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+fn encode_head(data: &mut Vec<u8>, major: u8, value: u64) {
+    let major = major << 5;
+    if value < 24 {
+        data.push(major | value as u8);
+    } else if value <= u8::MAX as u64 {
+        data.push(major | 24);
+        data.push(value as u8);
+    } else if value <= u16::MAX as u64 {
+        data.push(major | 25);
+        data.extend_from_slice(&(value as u16).to_be_bytes());
+    } else if value <= u32::MAX as u64 {
+        data.push(major | 26);
+        data.extend_from_slice(&(value as u32).to_be_bytes());
+    } else {
+        data.push(major | 27);
+        data.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+fn take<'a>(data: &'a [u8], offset: &mut usize, len: usize) -> Result<&'a [u8], AppError> {
+    let end = offset
+        .checked_add(len)
+        .filter(|end| *end <= data.len())
+        .ok_or(invalid("truncated CBOR data"))?;
+    let bytes = &data[*offset..end];
+    *offset = end;
+    Ok(bytes)
+}
+
+fn decode_item(data: &[u8], offset: &mut usize, depth: usize) -> Result<Cbor, AppError> {
+    if depth > 16 {
+        return Err(invalid("CBOR data nested too deep"));
+    }
+
+    let head = take(data, offset, 1)?[0];
+    let (major, info) = (head >> 5, head & 0x1f);
+    let value = match info {
+        0..=23 => info as u64,
+        24 => take(data, offset, 1)?[0] as u64,
+        25 => u16::from_be_bytes(take(data, offset, 2)?.try_into().unwrap()) as u64,
+        26 => u32::from_be_bytes(take(data, offset, 4)?.try_into().unwrap()) as u64,
+        27 => u64::from_be_bytes(take(data, offset, 8)?.try_into().unwrap()),
+        _ => return Err(invalid("unsupported CBOR length")),
+    };
+
+    match major {
+        0 => Ok(Cbor::Unsigned(value)),
+        2 => Ok(Cbor::Bytes(take(data, offset, value as usize)?.to_vec())),
+        3 => String::from_utf8(take(data, offset, value as usize)?.to_vec())
+            .map(Cbor::Text)
+            .map_err(|_| invalid("invalid CBOR text")),
+        4 => {
+            let mut items = vec![];
+            for _ in 0..value {
+                items.push(decode_item(data, offset, depth + 1)?);
+            }
+            Ok(Cbor::Array(items))
+        }
+        5 => {
+            let mut entries = vec![];
+            for _ in 0..value {
+                let key = decode_item(data, offset, depth + 1)?;
+                entries.push((key, decode_item(data, offset, depth + 1)?));
+            }
+            Ok(Cbor::Map(entries))
+        }
+        _ => Err(invalid("unsupported CBOR type")),
+    }
+}
+
+/// Split a message into UR parts of at most `max_fragment_len` bytes each. Messages
+/// fitting a single fragment use the single-part form `ur:<type>/<bytewords>`.
+pub fn encode_ur(ur_type: &str, message: &[u8], max_fragment_len: usize) -> Vec<String> {
+    let max_fragment_len = max_fragment_len.max(MIN_FRAGMENT_LEN);
+    if message.len() <= max_fragment_len {
+        return vec![format!("ur:{}/{}", ur_type, bytewords_encode(message))];
+    }
+
+    let mut seq_len = 1;
+    let fragment_len = loop {
+        let fragment_len = message.len().div_ceil(seq_len);
+        if fragment_len <= max_fragment_len {
+            break fragment_len;
+        }
+        seq_len += 1;
+    };
+
+    let checksum = crc32fast::hash(message);
+    let mut padded = message.to_vec();
+    padded.resize(fragment_len * seq_len, 0);
+    padded
+        .chunks(fragment_len)
+        .enumerate()
+        .map(|(index, fragment)| {
+            let part = Cbor::Array(vec![
+                Cbor::Unsigned(index as u64 + 1),
+                Cbor::Unsigned(seq_len as u64),
+                Cbor::Unsigned(message.len() as u64),
+                Cbor::Unsigned(checksum as u64),
+                Cbor::Bytes(fragment.to_vec()),
+            ]);
+            format!(
+                "ur:{}/{}-{}/{}",
+                ur_type,
+                index + 1,
+                seq_len,
+                bytewords_encode(&part.encode())
+            )
+        })
+        .collect()
+}
+
+struct Fragment {
+    seq_num: usize,
+    seq_len: usize,
+    message_len: usize,
+    checksum: u32,
+    data: Vec<u8>,
+}
+
+fn decode_fragment(body: &[u8]) -> Result<Fragment, AppError> {
+    let items = match Cbor::decode(body)? {
+        Cbor::Array(items) if items.len() == 5 => items,
+        _ => return Err(invalid("invalid UR part")),
+    };
+    let number = |index: usize| items[index].as_unsigned().ok_or(invalid("invalid UR part"));
+
+    Ok(Fragment {
+        seq_num: number(0)? as usize,
+        seq_len: number(1)? as usize,
+        message_len: number(2)? as usize,
+        checksum: u32::try_from(number(3)?).map_err(|_| invalid("invalid UR part"))?,
+        data: items[4]
+            .as_bytes()
+            .ok_or(invalid("invalid UR part"))?
+            .to_vec(),
+    })
+}
+
+/// Reassemble a UR from its parts, in any order and with duplicates. Returns the UR type
+/// and the message.
+pub fn decode_ur(parts: &[String]) -> Result<(String, Vec<u8>), AppError> {
+    let mut ur_type: Option<String> = None;
+    let mut fragments: Vec<Option<Vec<u8>>> = vec![];
+    let mut header: Option<(usize, u32)> = None;
+
+    for part in parts {
+        let part = part.trim().to_lowercase();
+        let path = part
+            .strip_prefix("ur:")
+            .ok_or(invalid("UR part must start with ur:"))?;
+        let components: Vec<&str> = path.split('/').collect();
+
+        let part_type = components[0].to_owned();
+        if *ur_type.get_or_insert_with(|| part_type.clone()) != part_type {
+            return Err(invalid("UR parts have different types"));
+        }
+
+        match components[..] {
+            [_, body] => {
+                if parts.len() != 1 {
+                    return Err(invalid("single-part UR cannot be combined"));
+                }
+                return Ok((part_type, bytewords_decode(body)?));
+            }
+            [_, _, body] => {
+                let fragment = decode_fragment(&bytewords_decode(body)?)?;
+                if fragment.seq_len == 0
+                    || fragment.seq_num == 0
+                    || fragment.seq_len > MAX_SEQ_LEN
+                    || fragment.seq_len > fragment.message_len.div_ceil(MIN_FRAGMENT_LEN)
+                {
+                    return Err(invalid("invalid UR sequence"));
+                }
+                if fragment.message_len > fragment.seq_len * fragment.data.len() {
+                    return Err(invalid("UR message is longer than its fragments"));
+                }
+                if fragments.is_empty() {
+                    fragments = vec![None; fragment.seq_len];
+                    header = Some((fragment.message_len, fragment.checksum));
+                }
+                if fragments.len() != fragment.seq_len
+                    || header != Some((fragment.message_len, fragment.checksum))
+                {
+                    return Err(invalid("UR parts belong to different messages"));
+                }
+                // Fountain parts mix several fragments, the pure ones are enough
+                if fragment.seq_num <= fragment.seq_len {
+                    fragments[fragment.seq_num - 1] = Some(fragment.data);
+                }
+            }
+            _ => return Err(invalid("invalid UR part")),
+        }
+    }
+
+    let (message_len, checksum) = header.ok_or(invalid("No UR part"))?;
+    let missing: Vec<String> = fragments
+        .iter()
+        .enumerate()
+        .filter(|(_, fragment)| fragment.is_none())
+        .map(|(index, _)| (index + 1).to_string())
+        .collect();
+    if !missing.is_empty() {
+        return Err(invalid(&format!(
+            "Missing UR parts: {} of {}",
+            missing.join(", "),
+            fragments.len()
+        )));
+    }
+
+    let mut message: Vec<u8> = fragments.into_iter().flatten().flatten().collect();
+    if message.len() < message_len {
+        return Err(invalid("UR message is shorter than announced"));
+    }
+    message.truncate(message_len);
+    if crc32fast::hash(&message) != checksum {
+        return Err(invalid("invalid UR message checksum"));
+    }
+    Ok((ur_type.unwrap_or_default(), message))
+}
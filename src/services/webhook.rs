@@ -0,0 +1,455 @@
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::sha2::Sha256;
+use rand::Rng;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::redirect::Policy;
+use reqwest::Url;
+use tokio::net::lookup_host;
+
+use crate::config;
+use crate::models::multi_sig_account::MultiSigInfo;
+use crate::models::webhook::{Webhook, WebhookDelivery};
+use crate::repositories::multi_sig_account::MultiSigDao;
+use crate::repositories::webhook::WebhookDao;
+use crate::serialize::error::AppError;
+use crate::serialize::webhook::{
+    DeliveryFilters, ListDeliveriesRes, NewWebhookReq, NewWebhookRes, UpdateWebhookReq,
+};
+use crate::serialize::PaginationRes;
+use crate::services::events::ACCOUNT_EVENTS;
+
+const MAX_WEBHOOKS_PER_ACCOUNT: usize = 10;
+const MAX_RESPONSE_BODY_LENGTH: usize = 1000;
+const MAX_RETRY_DELAY_SECS: i32 = 6 * 3600;
+const DELIVERY_BATCH_SIZE: i64 = 20;
+
+#[derive(Clone, Debug)]
+pub struct WebhookSrv {
+    webhook_dao: WebhookDao,
+    multi_sig_dao: MultiSigDao,
+    client: reqwest::Client,
+}
+
+impl WebhookSrv {
+    pub fn new(webhook_dao: WebhookDao, multi_sig_dao: MultiSigDao) -> Self {
+        let timeout_secs: u64 = config::get("webhook_timeout_secs");
+        WebhookSrv {
+            webhook_dao: webhook_dao.clone(),
+            multi_sig_dao: multi_sig_dao.clone(),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(timeout_secs))
+                .dns_resolver(Arc::new(PublicResolver))
+                .redirect(Policy::none())
+                .build()
+                .unwrap(),
+        }
+    }
+
+    // Only signers of the account can manage its webhooks
+    async fn request_multi_sig_info(
+        &self,
+        multi_sig_address: &str,
+        user_address: &str,
+    ) -> Result<MultiSigInfo, AppError> {
+        self.multi_sig_dao
+            .request_multi_sig_info_by_user(multi_sig_address, user_address)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("not found"))
+    }
+
+    async fn request_webhook(&self, user_address: &str, id: i32) -> Result<Webhook, AppError> {
+        let webhook = self
+            .webhook_dao
+            .get_webhook(id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .ok_or(AppError::new(404).message("Webhook not found"))?;
+        self.request_multi_sig_info(&webhook.multi_sig_address, user_address)
+            .await
+            .map_err(|_| AppError::new(404).message("Webhook not found"))?;
+        Ok(webhook)
+    }
+
+    fn validate_events(events: &[String]) -> Result<Vec<String>, AppError> {
+        if events.is_empty() {
+            return Ok(ACCOUNT_EVENTS
+                .iter()
+                .map(|event| event.to_string())
+                .collect());
+        }
+
+        let mut validated: Vec<String> = vec![];
+        for event in events {
+            if !ACCOUNT_EVENTS.contains(&event.as_str()) {
+                return Err(AppError::new(400).message(&format!("Unknown event {}", event)));
+            }
+            if !validated.contains(event) {
+                validated.push(event.clone());
+            }
+        }
+        Ok(validated)
+    }
+
+    pub async fn list_webhooks(
+        &self,
+        user_address: &str,
+        multi_sig_address: &str,
+    ) -> Result<Vec<Webhook>, AppError> {
+        let multi_sig_info = self
+            .request_multi_sig_info(multi_sig_address, user_address)
+            .await?;
+
+        self.webhook_dao
+            .get_webhooks_by_address(&multi_sig_info.multi_sig_address)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))
+    }
+
+    pub async fn create_webhook(
+        &self,
+        user_address: &str,
+        multi_sig_address: &str,
+        req: NewWebhookReq,
+    ) -> Result<NewWebhookRes, AppError> {
+        let multi_sig_info = self
+            .request_multi_sig_info(multi_sig_address, user_address)
+            .await?;
+        let url = validate_webhook_url(&req.url).await?;
+        let events = Self::validate_events(&req.events)?;
+
+        let webhooks = self
+            .webhook_dao
+            .get_webhooks_by_address(&multi_sig_info.multi_sig_address)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        if webhooks.len() >= MAX_WEBHOOKS_PER_ACCOUNT {
+            return Err(AppError::new(400).message(&format!(
+                "An account can not have more than {} webhooks",
+                MAX_WEBHOOKS_PER_ACCOUNT
+            )));
+        }
+
+        let secret = hex::encode(rand::thread_rng().gen::<[u8; 32]>());
+        let webhook = self
+            .webhook_dao
+            .create_webhook(
+                &multi_sig_info.multi_sig_address,
+                &url,
+                &secret,
+                &events,
+                &user_address.to_owned(),
+            )
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+
+        Ok(NewWebhookRes { webhook, secret })
+    }
+
+    pub async fn update_webhook(
+        &self,
+        user_address: &str,
+        id: i32,
+        req: UpdateWebhookReq,
+    ) -> Result<Webhook, AppError> {
+        let webhook = self.request_webhook(user_address, id).await?;
+        let url = match req.url {
+            Some(url) => validate_webhook_url(&url).await?,
+            None => webhook.url,
+        };
+        let events = match req.events {
+            Some(events) => Self::validate_events(&events)?,
+            None => webhook.events,
+        };
+
+        self.webhook_dao
+            .update_webhook(
+                webhook.id,
+                &url,
+                &events,
+                req.active.unwrap_or(webhook.active),
+            )
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))
+    }
+
+    pub async fn delete_webhook(&self, user_address: &str, id: i32) -> Result<bool, AppError> {
+        let webhook = self.request_webhook(user_address, id).await?;
+
+        self.webhook_dao
+            .delete_webhook(webhook.id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))
+    }
+
+    pub async fn list_deliveries(
+        &self,
+        user_address: &str,
+        id: i32,
+        filters: DeliveryFilters,
+    ) -> Result<ListDeliveriesRes, AppError> {
+        let webhook = self.request_webhook(user_address, id).await?;
+        let limit: i64 = filters.limit.unwrap_or(10);
+        let page: i64 = filters.page.unwrap_or(1);
+
+        let deliveries = self
+            .webhook_dao
+            .get_deliveries(webhook.id, (page - 1) * limit, limit)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        let total_record = self
+            .webhook_dao
+            .count_deliveries(webhook.id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        let total_page = total_record as f64 / limit as f64;
+
+        Ok(ListDeliveriesRes {
+            deliveries,
+            pagination: PaginationRes {
+                page,
+                limit,
+                total_records: total_record,
+                total_page: total_page.ceil() as i64,
+            },
+        })
+    }
+
+    pub async fn redeliver(
+        &self,
+        user_address: &str,
+        id: i32,
+        delivery_id: i32,
+    ) -> Result<WebhookDelivery, AppError> {
+        let webhook = self.request_webhook(user_address, id).await?;
+        let delivery = self
+            .webhook_dao
+            .get_delivery(delivery_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?
+            .filter(|delivery| delivery.webhook_id == webhook.id)
+            .ok_or(AppError::new(404).message("Delivery not found"))?;
+
+        self.webhook_dao
+            .redeliver(delivery.id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))
+    }
+
+    /// Send the deliveries which are due. Failures are retried with exponential backoff
+    /// until `webhook_max_attempts` is reached.
+    pub async fn deliver_due(&self) -> Result<(), AppError> {
+        let timeout_secs: i32 = config::get("webhook_timeout_secs");
+        let deliveries = self
+            .webhook_dao
+            .claim_due_deliveries(DELIVERY_BATCH_SIZE, timeout_secs * 2)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+
+        for delivery in deliveries {
+            if let Err(err) = self.deliver(&delivery).await {
+                log::warn!("webhook delivery {} failed: {}", delivery.id, err);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn deliver(&self, delivery: &WebhookDelivery) -> Result<(), AppError> {
+        let webhook = self
+            .webhook_dao
+            .get_webhook(delivery.webhook_id)
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))?;
+        let webhook = match webhook.filter(|webhook| webhook.active) {
+            Some(webhook) => webhook,
+            None => {
+                return self
+                    .record_failure(delivery, None, None, "Webhook disabled", false)
+                    .await
+            }
+        };
+        let url_error = validate_webhook_url(&webhook.url)
+            .await
+            .err()
+            .map(|err| err.to_string());
+        if let Some(error) = url_error {
+            return self
+                .record_failure(delivery, None, None, &error, true)
+                .await;
+        }
+
+        let timestamp = Utc::now().timestamp();
+        let result = self
+            .client
+            .post(&webhook.url)
+            .header("Content-Type", "application/json")
+            .header("X-Webhook-Id", webhook.id.to_string())
+            .header("X-Webhook-Delivery", delivery.id.to_string())
+            .header("X-Webhook-Event", &delivery.event)
+            .header("X-Webhook-Timestamp", timestamp.to_string())
+            .header(
+                "X-Webhook-Signature",
+                format!(
+                    "sha256={}",
+                    sign_payload(&webhook.secret, timestamp, &delivery.payload)
+                ),
+            )
+            .body(delivery.payload.clone())
+            .send()
+            .await;
+
+        let response = match result {
+            Ok(response) => response,
+            Err(err) => {
+                return self
+                    .record_failure(delivery, None, None, &err.to_string(), true)
+                    .await
+            }
+        };
+        let status = response.status();
+        let body: String = response
+            .text()
+            .await
+            .unwrap_or_default()
+            .chars()
+            .take(MAX_RESPONSE_BODY_LENGTH)
+            .collect();
+
+        if status.is_success() {
+            return self
+                .webhook_dao
+                .mark_delivered(delivery.id, status.as_u16() as i32, &body)
+                .await
+                .map_err(|err| AppError::new(500).message(&err.to_string()));
+        }
+        self.record_failure(
+            delivery,
+            Some(status.as_u16() as i32),
+            Some(body),
+            &format!("Endpoint responded {}", status),
+            true,
+        )
+        .await
+    }
+
+    async fn record_failure(
+        &self,
+        delivery: &WebhookDelivery,
+        response_status: Option<i32>,
+        response_body: Option<String>,
+        error: &str,
+        retryable: bool,
+    ) -> Result<(), AppError> {
+        let max_attempts: i32 = config::get("webhook_max_attempts");
+        let backoff_secs: i32 = config::get("webhook_retry_backoff_secs");
+
+        let attempts = delivery.attempts + 1;
+        let retry_in_secs = (retryable && attempts < max_attempts).then(|| {
+            backoff_secs
+                .saturating_mul(2i32.saturating_pow(attempts as u32 - 1))
+                .min(MAX_RETRY_DELAY_SECS)
+        });
+
+        self.webhook_dao
+            .mark_attempt_failed(
+                delivery.id,
+                response_status,
+                response_body,
+                &error.to_owned(),
+                retry_in_secs,
+            )
+            .await
+            .map_err(|err| AppError::new(500).message(&err.to_string()))
+    }
+}
+
+// Webhooks can point anywhere on the internet but never into the API's own network, so
+// the host must only resolve to public addresses
+async fn validate_webhook_url(target: &str) -> Result<String, AppError> {
+    let url = Url::parse(target.trim())
+        .map_err(|err| AppError::new(400).cause(err).message("Invalid webhook url"))?;
+    let host = match url.host_str() {
+        Some(host) if matches!(url.scheme(), "https" | "http") => host,
+        _ => return Err(AppError::new(400).message("Webhook url must be an http(s) url")),
+    };
+
+    let public = match host
+        .trim_matches(|c| c == '[' || c == ']')
+        .parse::<IpAddr>()
+    {
+        Ok(ip) => is_public_ip(ip),
+        Err(_) => resolve_public_host(host, url.port_or_known_default().unwrap_or(80))
+            .await
+            .is_ok(),
+    };
+    if !public {
+        return Err(AppError::new(400).message("Webhook url must resolve to a public address"));
+    }
+    Ok(url.to_string())
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            // 0.0.0.0/8 and the 100.64.0.0/10 carrier-grade NAT range aren't covered by std
+            let reserved = a == 0 || (a == 100 && (64..128).contains(&b));
+            !(reserved
+                || ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation())
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ip(IpAddr::V4(ip)),
+            None => {
+                let first = ip.segments()[0];
+                // fc00::/7 unique local and fe80::/10 link-local
+                let local = (first & 0xfe00) == 0xfc00 || (first & 0xffc0) == 0xfe80;
+                !(local || ip.is_loopback() || ip.is_unspecified() || ip.is_multicast())
+            }
+        },
+    }
+}
+
+async fn resolve_public_host(host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+    let addrs: Vec<SocketAddr> = lookup_host((host, port)).await?.collect();
+    if addrs.is_empty() || !addrs.iter().all(|addr| is_public_ip(addr.ip())) {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "webhook host resolves to a non public address",
+        ));
+    }
+    Ok(addrs)
+}
+
+// Deliveries resolve the host again when connecting, so a DNS record changed after the
+// url was validated can't send them to a private address
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs = resolve_public_host(name.as_str(), 0).await?;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Hex HMAC-SHA256 of `{timestamp}.{payload}`. The timestamp is signed too so receivers
+/// can reject replayed deliveries.
+pub fn sign_payload(secret: &str, timestamp: i64, payload: &str) -> String {
+    let mut mac = Hmac::new(Sha256::new(), secret.as_bytes());
+    mac.input(format!("{}.{}", timestamp, payload).as_bytes());
+    hex::encode(mac.result().code())
+}
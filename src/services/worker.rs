@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use crate::config;
+
+use super::email::EmailSrv;
+use super::events::EventHub;
+use super::multi_sig_account::MultiSigSrv;
+use super::webhook::WebhookSrv;
+
+/// Poll the node for broadcast transactions until they are confirmed or dropped, and
+/// rebroadcast the ones which could not reach the node.
+pub async fn run_confirmation_tracker(multi_sig_srv: MultiSigSrv) {
+    let interval_secs: u64 = config::get("tracker_interval_secs");
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+
+    loop {
+        interval.tick().await;
+        if let Err(err) = multi_sig_srv.sync_in_progress_transactions().await {
+            log::error!("confirmation tracker failed: {}", err);
+        }
+        if let Err(err) = multi_sig_srv.retry_transport_failures().await {
+            log::error!("broadcast retry failed: {}", err);
+        }
+    }
+}
+
+/// Expire pending proposals which passed their deadline or lost their inputs.
+pub async fn run_expiry_sweep(multi_sig_srv: MultiSigSrv) {
+    let interval_secs: u64 = config::get("expiry_sweep_interval_secs");
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+
+    loop {
+        interval.tick().await;
+        if let Err(err) = multi_sig_srv.expire_pending_transactions().await {
+            log::error!("expiry sweep failed: {}", err);
+        }
+    }
+}
+
+/// Send the queued webhook deliveries which are due.
+pub async fn run_webhook_delivery(webhook_srv: WebhookSrv) {
+    let interval_secs: u64 = config::get("webhook_delivery_interval_secs");
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+
+    loop {
+        interval.tick().await;
+        if let Err(err) = webhook_srv.deliver_due().await {
+            log::error!("webhook delivery failed: {}", err);
+        }
+    }
+}
+
+/// Keep a LISTEN connection open and feed the received events to the open streams.
+pub async fn run_event_listener(event_hub: EventHub) {
+    let retry_secs: u64 = config::get("event_listener_retry_secs");
+
+    loop {
+        match event_hub.listen().await {
+            Ok(_) => log::warn!("event listener connection closed"),
+            Err(err) => log::error!("event listener failed: {}", err),
+        }
+        tokio::time::sleep(Duration::from_secs(retry_secs)).await;
+    }
+}
+
+/// Send the emails waiting in the outbox.
+pub async fn run_email_outbox(email_srv: EmailSrv) {
+    let interval_secs: u64 = config::get("email_delivery_interval_secs");
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+
+    loop {
+        interval.tick().await;
+        if let Err(err) = email_srv.send_due().await {
+            log::error!("email outbox failed: {}", err);
+        }
+    }
+}